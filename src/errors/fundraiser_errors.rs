@@ -0,0 +1,104 @@
+use pinocchio::program_error::ProgramError;
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FundraiserError {
+    /// The vault does not hold enough tokens to meet the fundraising target.
+    TargetNotMet = 0,
+    /// The fundraiser has been cancelled and only accepts refunds.
+    FundraiserNotActive = 1,
+    /// The milestone configuration passed to initialize is invalid.
+    InvalidMilestones = 2,
+    /// The next tranche has not reached its unlock time yet.
+    TrancheLocked = 3,
+    /// Every tranche has already been released.
+    NoTranchesRemaining = 4,
+    /// The action is not available for the way this fundraiser releases funds.
+    InvalidReleaseMode = 5,
+    /// Votes are not being accepted for this tranche right now.
+    VotingClosed = 6,
+    /// Contributors are still voting on the next tranche.
+    VotingInProgress = 7,
+    /// The next tranche did not reach quorum or was rejected by contributors. The maker
+    /// can re-open the vote.
+    VoteNotPassed = 8,
+    /// Contributors were not given the right to cancel this fundraiser.
+    CancellationDisabled = 9,
+    /// Nothing has vested since the last claim.
+    NothingToClaim = 10,
+    /// SOL can only be wrapped or unwrapped when the fundraiser raises the native mint.
+    NotNativeMint = 11,
+    /// The mint was not registered as accepted by this fundraiser.
+    MintNotAccepted = 12,
+    /// The fundraiser already accepts the maximum number of mints.
+    TooManyMints = 13,
+    /// The price account is not publishing a usable price.
+    InvalidPrice = 14,
+    /// The price account has not been updated recently enough.
+    StalePrice = 15,
+    /// The confidence interval of the price is too wide.
+    PriceTooUncertain = 16,
+    /// The contributor is not included in the fundraiser's allowlist.
+    NotAllowlisted = 17,
+    /// The contribution would exceed the contributor's allowlisted cap.
+    ContributionCapExceeded = 18,
+    /// The contributor does not hold enough of the gate mint.
+    GateNotMet = 19,
+    /// The contributor's attestation has expired or was revoked.
+    AttestationExpired = 20,
+    /// The maker refuses contributions from this wallet.
+    ContributorBlocked = 21,
+    /// The position has been voted with and cannot change hands or be refunded until
+    /// the vote ends.
+    PositionLocked = 22,
+    /// The contributor already claimed their share of the rewards.
+    RewardAlreadyClaimed = 23,
+    /// Rewards are only paid out once the fundraiser has succeeded.
+    NotSucceeded = 24,
+    /// The tier configuration passed to initialize is invalid.
+    InvalidTiers = 25,
+    /// Every slot of the selected tier has been taken.
+    TierSoldOut = 26,
+    /// The contribution does not reach the price of the selected tier.
+    BelowTierPrice = 27,
+    /// The contributor already holds a tier.
+    TierAlreadySelected = 28,
+    /// The matching pool only returns its funds once the fundraiser is over.
+    MatchingPoolLocked = 29,
+    /// The quadratic funding round has been finalized.
+    RoundClosed = 30,
+    /// The quadratic funding round has not ended yet.
+    RoundNotEnded = 31,
+    /// The quadratic funding round already has the maximum number of projects.
+    RoundFull = 32,
+    /// Positions of fundraisers in a quadratic funding round cannot change hands.
+    PositionInRound = 33,
+    /// The beneficiaries or the allocation among them are invalid.
+    InvalidAllocation = 34,
+    /// Pledges are never paid in before the raise succeeds, so there is nothing to refund.
+    PledgeNotRefundable = 35,
+    /// Some pledges have not been collected yet.
+    PledgesNotCollected = 36,
+    /// Pledges are only collected once the fundraiser has ended.
+    FundraiserNotEnded = 37,
+    /// The next payment of the subscription is not due yet.
+    SubscriptionNotDue = 38,
+    /// The contribution intent is not signed by the contributor.
+    InvalidIntentSignature = 39,
+    /// The contribution intent has expired.
+    IntentExpired = 40,
+    /// Refunds are only paid once the fundraiser was cancelled or missed its target.
+    NotRefundable = 41,
+    /// Some contributors have not claimed their reward yet and the claim period is not over.
+    RewardsOutstanding = 42,
+    /// The fundraiser has neither succeeded nor failed yet.
+    OutcomePending = 43,
+    /// The tranche was approved, so its vote cannot be re-opened.
+    VotePassed = 44,
+}
+
+impl From<FundraiserError> for ProgramError {
+    fn from(e: FundraiserError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
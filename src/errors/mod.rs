@@ -1 +1,4 @@
-mod initialize_errors;
\ No newline at end of file
+mod fundraiser_errors;
+mod initialize_errors;
+
+pub use fundraiser_errors::*;
@@ -0,0 +1,31 @@
+use crate::helpers::load_acc_data_mut_unchecked;
+use crate::state::CampaignData;
+use pinocchio::ProgramResult;
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::Pubkey;
+
+/// Rolls a change of a child fundraiser up into its campaign: `added` and `removed`
+/// amounts of the raise, and whether a contributor joined or left the child.
+#[inline(always)]
+pub fn record_in_campaign(
+    campaign: &AccountInfo,
+    expected_campaign: &Pubkey,
+    added: u64,
+    removed: u64,
+    joined: bool,
+    left: bool,
+) -> ProgramResult {
+    if campaign.key().ne(expected_campaign) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !campaign.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if !campaign.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+
+    let data = &mut campaign.try_borrow_mut_data()?;
+    load_acc_data_mut_unchecked::<CampaignData>(data)?.record(added, removed, joined, left)
+}
@@ -0,0 +1,48 @@
+use crate::helpers::load_acc_data_mut_unchecked;
+use crate::state::{CANCEL_PROPOSAL_SEED, CANCEL_VOTE_SEED, CancelProposalData, VoteRecordData};
+use pinocchio::ProgramResult;
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey;
+
+/// Takes up to `amount` of `contributor`'s weight back out of the cancellation proposal of
+/// `fundraiser`, so refunded money cannot be voted with again from another wallet.
+#[inline(always)]
+pub fn withdraw_cancel_vote(
+    fundraiser: &AccountInfo,
+    contributor: &AccountInfo,
+    cancel_proposal: &AccountInfo,
+    cancel_vote_record: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    let seeds: &[&[u8]] = &[CANCEL_PROPOSAL_SEED, fundraiser.key()];
+    let (cancel_proposal_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if cancel_proposal_pda.ne(cancel_proposal.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let seeds: &[&[u8]] = &[CANCEL_VOTE_SEED, fundraiser.key(), contributor.key()];
+    let (cancel_vote_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if cancel_vote_pda.ne(cancel_vote_record.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !cancel_proposal.is_owned_by(&crate::ID) || !cancel_vote_record.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if !cancel_proposal.is_writable() || !cancel_vote_record.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+
+    let withdrawn = {
+        let data = &mut cancel_vote_record.try_borrow_mut_data()?;
+        let vote_state = load_acc_data_mut_unchecked::<VoteRecordData>(data)?;
+        let withdrawn = vote_state.weight().min(amount);
+        vote_state.set_weight(vote_state.weight() - withdrawn);
+        withdrawn
+    };
+
+    let data = &mut cancel_proposal.try_borrow_mut_data()?;
+    let proposal_state = load_acc_data_mut_unchecked::<CancelProposalData>(data)?;
+    proposal_state.set_votes(proposal_state.votes().saturating_sub(withdrawn));
+
+    Ok(())
+}
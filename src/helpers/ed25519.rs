@@ -0,0 +1,72 @@
+use crate::errors::FundraiserError;
+use crate::state::ED25519_PROGRAM_ID;
+use pinocchio::ProgramResult;
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::Pubkey;
+use pinocchio::sysvars::instructions::Instructions;
+
+/// Size of the offsets the Ed25519 program reads for each signature.
+const OFFSETS_LEN: usize = 14;
+
+/// Offsets pointing into the Ed25519 instruction itself rather than another one.
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, ProgramError> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or(FundraiserError::InvalidIntentSignature.into())
+}
+
+fn read_slice(data: &[u8], offset: u16, len: usize) -> Result<&[u8], ProgramError> {
+    data.get(offset as usize..offset as usize + len)
+        .ok_or(FundraiserError::InvalidIntentSignature.into())
+}
+
+/// Checks that the data of an Ed25519 program instruction verifies a single signature
+/// of `signer` over exactly `message`, all carried by that same instruction. The
+/// runtime has already rejected the transaction if the signature itself is invalid.
+pub fn check_ed25519_data(data: &[u8], signer: &Pubkey, message: &[u8]) -> ProgramResult {
+    if data.len() < 2 + OFFSETS_LEN || data[0] != 1 {
+        return Err(FundraiserError::InvalidIntentSignature.into());
+    }
+
+    let signature_index = read_u16(data, 4)?;
+    let public_key_offset = read_u16(data, 6)?;
+    let public_key_index = read_u16(data, 8)?;
+    let message_offset = read_u16(data, 10)?;
+    let message_size = read_u16(data, 12)?;
+    let message_index = read_u16(data, 14)?;
+    if signature_index != THIS_INSTRUCTION
+        || public_key_index != THIS_INSTRUCTION
+        || message_index != THIS_INSTRUCTION
+    {
+        return Err(FundraiserError::InvalidIntentSignature.into());
+    }
+
+    if read_slice(data, public_key_offset, 32)? != signer.as_ref()
+        || read_slice(data, message_offset, message_size as usize)? != message
+    {
+        return Err(FundraiserError::InvalidIntentSignature.into());
+    }
+
+    Ok(())
+}
+
+/// Checks that the instruction right before the current one has the Ed25519 program
+/// verify `signer`'s signature over `message`.
+pub fn check_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> ProgramResult {
+    let instructions = Instructions::try_from(instructions_sysvar)?;
+    let instruction = instructions
+        .get_instruction_relative(-1)
+        .map_err(|_| FundraiserError::InvalidIntentSignature)?;
+    if instruction.get_program_id().ne(&ED25519_PROGRAM_ID) {
+        return Err(FundraiserError::InvalidIntentSignature.into());
+    }
+
+    check_ed25519_data(instruction.get_instruction_data(), signer, message)
+}
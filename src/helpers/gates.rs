@@ -0,0 +1,97 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{
+    allowlist_leaf, load_acc_data_mut_unchecked, split_ix_data, verify_merkle_proof,
+};
+use crate::state::{ATTESTATION_SEED, AllowlistProofIxData, AttestationData, BLOCK_SEED};
+use pinocchio::ProgramResult;
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::{self, Pubkey};
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+use pinocchio_token::state::TokenAccount;
+
+/// Verifies the allowlist proof at the start of `data` for `contributor` and returns the
+/// contributor's cap along with the data following the proof.
+pub fn check_allowlist_proof<'a>(
+    contributor: &Pubkey,
+    root: &[u8; 32],
+    data: &'a [u8],
+) -> Result<(u64, &'a [u8]), ProgramError> {
+    let (proof_ix, rest) = split_ix_data::<AllowlistProofIxData>(data)?;
+    let proof_len = proof_ix.depth() as usize * 32;
+    if rest.len() < proof_len {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (proof, rest) = rest.split_at(proof_len);
+
+    let proof = bytemuck::try_cast_slice::<u8, [u8; 32]>(proof)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    let leaf = allowlist_leaf(contributor, proof_ix.cap());
+    if !verify_merkle_proof(leaf, proof, root) {
+        return Err(FundraiserError::NotAllowlisted.into());
+    }
+
+    Ok((proof_ix.cap(), rest))
+}
+
+/// Checks that `block_record` is the block record PDA of `contributor` and that the maker
+/// has not blocked them.
+pub fn check_not_blocked(
+    fundraiser: &AccountInfo,
+    contributor: &Pubkey,
+    block_record: &AccountInfo,
+) -> ProgramResult {
+    let seeds: &[&[u8]] = &[BLOCK_SEED, fundraiser.key(), contributor];
+    let (block_record_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if block_record_pda.ne(block_record.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !block_record.data_is_empty() {
+        return Err(FundraiserError::ContributorBlocked.into());
+    }
+
+    Ok(())
+}
+
+/// Checks that `gate_account` is a token account of `holder` holding at least `min_balance`
+/// of `gate_mint`.
+pub fn check_token_gate(
+    gate_mint: &Pubkey,
+    min_balance: u64,
+    holder: &Pubkey,
+    gate_account: &AccountInfo,
+) -> ProgramResult {
+    let gate_token = TokenAccount::from_account_info(gate_account)?;
+    if gate_token.owner().ne(holder)
+        || gate_token.mint().ne(gate_mint)
+        || gate_token.amount() < min_balance
+    {
+        return Err(FundraiserError::GateNotMet.into());
+    }
+
+    Ok(())
+}
+
+/// Checks that `attestation` is an unexpired attestation of `contributor` by `issuer`.
+pub fn check_attestation(
+    issuer: &Pubkey,
+    contributor: &Pubkey,
+    attestation: &AccountInfo,
+) -> ProgramResult {
+    let seeds: &[&[u8]] = &[ATTESTATION_SEED, issuer, contributor];
+    let (attestation_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if attestation_pda.ne(attestation.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !attestation.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let data = &mut attestation.try_borrow_mut_data()?;
+    let attestation_state = load_acc_data_mut_unchecked::<AttestationData>(data)?;
+    if Clock::get()?.unix_timestamp as u64 >= attestation_state.expires_at() {
+        return Err(FundraiserError::AttestationExpired.into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::state::MATCHING_SEED;
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::{self, Pubkey};
+use pinocchio_token::state::TokenAccount;
+
+/// Checks that `matching_pool` is the matching pool PDA of `fundraiser` and that
+/// `pool_vault` is a token account of `mint` owned by it. Returns the pool's bump and
+/// the balance of its vault.
+#[inline(always)]
+pub fn check_matching_pool(
+    fundraiser: &AccountInfo,
+    matching_pool: &AccountInfo,
+    pool_vault: &AccountInfo,
+    mint: &Pubkey,
+) -> Result<(u8, u64), ProgramError> {
+    let seeds: &[&[u8]] = &[MATCHING_SEED, fundraiser.key()];
+    let (pool_pda, bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if pool_pda.ne(matching_pool.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !pool_vault.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+
+    let vault_account = TokenAccount::from_account_info(pool_vault)?;
+    if vault_account.owner().ne(matching_pool.key()) || vault_account.mint().ne(mint) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok((bump, vault_account.amount()))
+}
@@ -0,0 +1,79 @@
+use pinocchio::program_error::ProgramError;
+
+/// Computes `amount * numerator / denominator` in 128-bit precision, rounding down.
+#[inline(always)]
+pub fn mul_div(amount: u64, numerator: u64, denominator: u64) -> Result<u64, ProgramError> {
+    if denominator == 0 {
+        return Err(ProgramError::ArithmeticOverflow);
+    }
+
+    let result = (amount as u128) * (numerator as u128) / (denominator as u128);
+    u64::try_from(result).map_err(|_| ProgramError::ArithmeticOverflow)
+}
+
+/// Share of `vault_remaining` owed to a contributor who put in `amount` out of
+/// `total_contributed`.
+///
+/// The share is rounded down. Every refund therefore takes at most its exact share,
+/// so the ratio of vault balance to outstanding contributions never drops and the
+/// remaining contributors can always be paid their own share. The last contributor
+/// to refund (`amount == total_contributed`) receives whatever is left, dust included.
+#[inline(always)]
+pub fn pro_rata_share(
+    amount: u64,
+    vault_remaining: u64,
+    total_contributed: u64,
+) -> Result<u64, ProgramError> {
+    if amount > total_contributed {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    mul_div(amount, vault_remaining, total_contributed)
+}
+
+/// Integer square root, rounded down. Computed digit by digit with integer operations
+/// only, so it is exact and cheap on the BPF target.
+#[inline(always)]
+pub fn isqrt(value: u64) -> u64 {
+    let mut remainder = value;
+    let mut root: u64 = 0;
+    let mut bit: u64 = 1 << 62;
+    while bit > remainder {
+        bit >>= 2;
+    }
+
+    while bit != 0 {
+        if remainder >= root + bit {
+            remainder -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+
+    root
+}
+
+/// Part of `pool` matched to a project of quadratic funding weight `weight` out of
+/// `total_weight`, rounded down.
+///
+/// Weights are squared sums and can use the full 128 bits, so the low bits of both are
+/// dropped until the total fits in 64 bits. The product with the pool then fits in 128
+/// bits. Dropping the bits costs each project up to about `pool / 2^63` on top of the
+/// rounding, an absolute error: once the total is that large, a project whose weight
+/// is below the dropped bits loses its whole match.
+#[inline(always)]
+pub fn quadratic_match(pool: u64, weight: u128, total_weight: u128) -> Result<u64, ProgramError> {
+    if total_weight == 0 {
+        return Err(ProgramError::ArithmeticOverflow);
+    }
+    if weight > total_weight {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let shift = 64u32.saturating_sub(total_weight.leading_zeros());
+    let (weight, total_weight) = (weight >> shift, total_weight >> shift);
+
+    Ok((pool as u128 * weight / total_weight) as u64)
+}
@@ -0,0 +1,53 @@
+use pinocchio::pubkey::Pubkey;
+
+/// Domain separators so a leaf can never be passed off as an inner node.
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// SHA-256 of the concatenation of `vals`.
+pub fn hashv(vals: &[&[u8]]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+
+    #[cfg(target_os = "solana")]
+    unsafe {
+        pinocchio::syscalls::sol_sha256(
+            vals as *const _ as *const u8,
+            vals.len() as u64,
+            hash.as_mut_ptr(),
+        );
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        for val in vals {
+            hasher.update(val);
+        }
+        hash.copy_from_slice(&hasher.finalize());
+    }
+
+    hash
+}
+
+/// Leaf of the allowlist tree for `contributor`. A `cap` of zero means uncapped.
+pub fn allowlist_leaf(contributor: &Pubkey, cap: u64) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, contributor.as_ref(), &cap.to_le_bytes()])
+}
+
+/// Parent of two nodes. Pairs are hashed in sorted order so proofs need no path bits.
+pub fn merkle_parent(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[NODE_PREFIX, a, b])
+    } else {
+        hashv(&[NODE_PREFIX, b, a])
+    }
+}
+
+pub fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| merkle_parent(&node, sibling))
+        .eq(root)
+}
@@ -1,5 +1,29 @@
 mod account_checks;
+mod campaign;
+mod cancel_vote;
+mod ed25519;
+mod gates;
+mod matching;
+mod math;
+mod merkle;
+mod oracle;
+mod plain_contribution;
+mod receipt;
+mod round;
 mod utils;
+mod vault;
 
 pub use account_checks::*;
-pub use utils::*;
\ No newline at end of file
+pub use campaign::*;
+pub use cancel_vote::*;
+pub use ed25519::*;
+pub use gates::*;
+pub use matching::*;
+pub use math::*;
+pub use merkle::*;
+pub use oracle::*;
+pub use plain_contribution::*;
+pub use receipt::*;
+pub use round::*;
+pub use utils::*;
+pub use vault::*;
@@ -0,0 +1,80 @@
+use crate::errors::FundraiserError;
+use crate::state::{BPS_SCALER, MAX_PRICE_AGE, MAX_PRICE_CONFIDENCE_BPS, USD_DECIMALS};
+use pinocchio::ProgramResult;
+use pinocchio::program_error::ProgramError;
+
+const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+const PYTH_PRICE_ACCOUNT_LEN: usize = 240;
+
+/// Aggregate price read from an account in the Pyth v2 price account layout.
+pub struct OraclePrice {
+    pub price: u64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < PYTH_PRICE_ACCOUNT_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let read = |offset: usize, len: usize| &data[offset..offset + len];
+        let read_u32 = |offset| u32::from_le_bytes(read(offset, 4).try_into().unwrap());
+        let read_u64 = |offset| u64::from_le_bytes(read(offset, 8).try_into().unwrap());
+
+        if read_u32(0) != PYTH_MAGIC || read_u32(8) != PYTH_PRICE_ACCOUNT_TYPE {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // only the aggregate price of a trading feed is usable
+        let price = read_u64(208) as i64;
+        if read_u32(224) != PYTH_STATUS_TRADING || price <= 0 {
+            return Err(FundraiserError::InvalidPrice.into());
+        }
+
+        Ok(Self {
+            price: price as u64,
+            conf: read_u64(216),
+            expo: read_u32(20) as i32,
+            publish_time: read_u64(96) as i64,
+        })
+    }
+
+    /// Rejects prices older than `MAX_PRICE_AGE` or with too wide a confidence interval.
+    pub fn check(&self, now: i64) -> ProgramResult {
+        if now.saturating_sub(self.publish_time) > MAX_PRICE_AGE {
+            return Err(FundraiserError::StalePrice.into());
+        }
+        if self.conf as u128 * BPS_SCALER as u128
+            > self.price as u128 * MAX_PRICE_CONFIDENCE_BPS as u128
+        {
+            return Err(FundraiserError::PriceTooUncertain.into());
+        }
+
+        Ok(())
+    }
+
+    /// USD value, with `USD_DECIMALS` decimals, of `amount` raw units of a token with
+    /// `decimals` decimals. Rounds down.
+    pub fn usd_value(&self, amount: u64, decimals: u8) -> Result<u64, ProgramError> {
+        let exponent = self.expo + USD_DECIMALS as i32 - decimals as i32;
+        let scale = 10u128
+            .checked_pow(exponent.unsigned_abs())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        let value = amount as u128 * self.price as u128;
+        let value = if exponent >= 0 {
+            value
+                .checked_mul(scale)
+                .ok_or(ProgramError::ArithmeticOverflow)?
+        } else {
+            value / scale
+        };
+
+        u64::try_from(value).map_err(|_| ProgramError::ArithmeticOverflow)
+    }
+}
@@ -0,0 +1,82 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{check_not_blocked, load_acc_data_mut_unchecked};
+use crate::state::{ContributorData, FundraiserData, FundraiserStatus};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::Pubkey;
+use pinocchio_token::state::TokenAccount;
+
+/// Credits `amount` paid into `vault` on behalf of `contributor` outside of Contribute,
+/// for fundraisers that take plain transfers. `remaining` holds the contributor's block
+/// record PDA once anyone is blocked. Returns the maker and bump the fundraiser signs with.
+pub fn credit_plain_contribution(
+    fundraiser: &AccountInfo,
+    contributor_account: &AccountInfo,
+    contributor: &Pubkey,
+    vault: &AccountInfo,
+    remaining: &[AccountInfo],
+    amount: u64,
+    now: u64,
+) -> Result<(Pubkey, u8), ProgramError> {
+    let (shares, maker, f_bump) = {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        if fundraiser_state.status()? != FundraiserStatus::Active
+            || now >= fundraiser_state.deadline()
+        {
+            return Err(FundraiserError::FundraiserNotActive.into());
+        }
+        if !fundraiser_state.takes_plain_transfers() {
+            return Err(FundraiserError::InvalidReleaseMode.into());
+        }
+        // round weights are only reported by Contribute
+        if fundraiser_state.in_round() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if fundraiser_state.blocked_count() > 0 {
+            let block_record = remaining
+                .first()
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            check_not_blocked(fundraiser, contributor, block_record)?;
+        }
+
+        let vault_account = TokenAccount::from_account_info(vault)?;
+        if vault_account.owner().ne(fundraiser.key())
+            || vault_account.mint().ne(&fundraiser_state.mint_to_raise())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        fundraiser_state.set_current_amount(
+            fundraiser_state
+                .current_amount()
+                .checked_add(amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+        let shares = fundraiser_state.shares_for(amount, now)?;
+        fundraiser_state.set_total_shares(
+            fundraiser_state
+                .total_shares()
+                .checked_add(shares)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+        (shares, fundraiser_state.maker(), fundraiser_state.bump())
+    };
+
+    let data = &mut contributor_account.try_borrow_mut_data()?;
+    let contributor_state = load_acc_data_mut_unchecked::<ContributorData>(data)?;
+    contributor_state.set_amount(
+        contributor_state
+            .amount()
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    );
+    contributor_state.set_shares(
+        contributor_state
+            .shares()
+            .checked_add(shares)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    );
+
+    Ok((maker, f_bump))
+}
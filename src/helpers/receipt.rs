@@ -0,0 +1,22 @@
+use crate::state::RECEIPT_SEED;
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey;
+
+/// Checks that `receipt_mint` is the receipt mint PDA of `fundraiser` and returns its bump.
+#[inline(always)]
+pub fn check_receipt_mint(
+    fundraiser: &AccountInfo,
+    receipt_mint: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let seeds: &[&[u8]] = &[RECEIPT_SEED, fundraiser.key()];
+    let (receipt_pda, bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if receipt_pda.ne(receipt_mint.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !receipt_mint.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+
+    Ok(bump)
+}
@@ -0,0 +1,49 @@
+use crate::helpers::{isqrt, load_acc_data_mut_unchecked};
+use crate::state::RoundData;
+use pinocchio::ProgramResult;
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::Pubkey;
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+
+/// Updates the quadratic funding weight of `fundraiser` in `round` after a contributor's
+/// total went from `previous` to `total`, replacing the square root of the old total by
+/// the new one. Rounds that have ended are left untouched.
+#[inline(always)]
+pub fn update_round_weight(
+    round: &AccountInfo,
+    expected_round: &Pubkey,
+    fundraiser: &Pubkey,
+    previous: u64,
+    total: u64,
+) -> ProgramResult {
+    if round.key().ne(expected_round) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !round.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if !round.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+
+    let data = &mut round.try_borrow_mut_data()?;
+    let round_state = load_acc_data_mut_unchecked::<RoundData>(data)?;
+    // the match is computed from the weights at `ends_at`
+    if round_state.finalized() || Clock::get()?.unix_timestamp as u64 >= round_state.ends_at() {
+        return Ok(());
+    }
+
+    let index = round_state
+        .find(fundraiser)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    let project = &mut round_state.projects[index];
+    let sqrt_sum = project
+        .sqrt_sum()
+        .checked_add(isqrt(total))
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        .saturating_sub(isqrt(previous));
+    project.set_sqrt_sum(sqrt_sum);
+
+    Ok(())
+}
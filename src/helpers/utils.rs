@@ -3,7 +3,7 @@ use pinocchio::instruction::Seed;
 use pinocchio::instruction::Signer;
 use pinocchio::sysvars::Sysvar;
 use pinocchio::sysvars::rent::Rent;
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
+use pinocchio::{ProgramResult, account_info::AccountInfo, program_error::ProgramError};
 use pinocchio_system::instructions::CreateAccount;
 
 pub trait DataLen {
@@ -81,6 +81,26 @@ where
     try_from_bytes(bytes).map_err(|_| ProgramError::InvalidInstructionData)
 }
 
+/// Loads a `T` from the front of `bytes` and returns it along with the trailing bytes,
+/// for instructions that carry optional sections after their fixed-size data.
+#[inline(always)]
+pub fn split_ix_data<T>(bytes: &[u8]) -> Result<(&T, &[u8]), ProgramError>
+where
+    T: DataLen + Pod + Zeroable,
+{
+    if bytes.len() < T::LEN {
+        pinocchio_log::log!(
+            "Unexpected data length split_ix_data : bytes.len(): {} - T::LEN: {}",
+            bytes.len(),
+            T::LEN
+        );
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let (head, rest) = bytes.split_at(T::LEN);
+    Ok((load_ix_data::<T>(head)?, rest))
+}
+
 #[inline(always)]
 pub fn create_pda_account<S>(
     payer: &AccountInfo,
@@ -102,3 +122,13 @@ where
 
     Ok(())
 }
+
+/// Closes an account owned by this program and moves its lamports to `destination`.
+/// The program can debit its own accounts, so no system program transfer is needed.
+#[inline(always)]
+pub fn close_pda_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    let lamports = account.lamports();
+    *destination.try_borrow_mut_lamports()? += lamports;
+
+    account.close()
+}
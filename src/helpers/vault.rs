@@ -0,0 +1,126 @@
+use crate::errors::FundraiserError;
+use crate::helpers::load_ix_data;
+use crate::state::{NATIVE_MINT, UnwrapIxData, VAULT_SEED};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::{Seed, Signer};
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::Pubkey;
+use pinocchio::sysvars::{Sysvar, rent::Rent};
+use pinocchio::{ProgramResult, pubkey};
+use pinocchio_token::instructions::CloseAccount;
+use pinocchio_token::state::TokenAccount;
+
+/// Checks that `vault` is the native SOL vault PDA of `fundraiser` and returns its bump.
+#[inline(always)]
+pub fn check_native_vault(
+    fundraiser: &AccountInfo,
+    vault: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let seeds: &[&[u8]] = &[VAULT_SEED, fundraiser.key()];
+    let (vault_pda, bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if vault_pda.ne(vault.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !vault.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if !vault.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+
+    Ok(bump)
+}
+
+/// Checks that `vault` is a token account of `mint` owned by `fundraiser`, so no other
+/// account of the fundraiser, such as its reward vault, can be passed in its place.
+#[inline(always)]
+pub fn check_token_vault(
+    fundraiser: &AccountInfo,
+    vault: &AccountInfo,
+    mint: &Pubkey,
+) -> ProgramResult {
+    if !vault.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+
+    let vault_account = TokenAccount::from_account_info(vault)?;
+    if vault_account.owner().ne(fundraiser.key()) || vault_account.mint().ne(mint) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+/// Funds available in the vault: its token balance, or for native vaults the lamports
+/// held above the rent-exempt reserve.
+#[inline(always)]
+pub fn vault_balance(vault: &AccountInfo, native: bool) -> Result<u64, ProgramError> {
+    if native {
+        let reserve = Rent::get()?.minimum_balance(vault.data_len());
+        return Ok(vault.lamports().saturating_sub(reserve));
+    }
+
+    Ok(TokenAccount::from_account_info(vault)?.amount())
+}
+
+/// Pays `amount` out of the fundraiser vault into `destination`.
+///
+/// Token vaults transfer with the fundraiser PDA as authority. Native vaults are owned
+/// by this program, so lamports are moved directly and the rent-exempt reserve is never
+/// paid out.
+#[inline(always)]
+pub fn transfer_from_vault(
+    vault: &AccountInfo,
+    destination: &AccountInfo,
+    fundraiser: &AccountInfo,
+    fundraiser_seeds: &[Seed],
+    amount: u64,
+    native: bool,
+) -> ProgramResult {
+    if native {
+        if amount > vault_balance(vault, true)? {
+            return Err(ProgramError::InsufficientFunds);
+        }
+        *vault.try_borrow_mut_lamports()? -= amount;
+        *destination.try_borrow_mut_lamports()? += amount;
+        return Ok(());
+    }
+
+    pinocchio_token::instructions::Transfer {
+        from: vault,
+        to: destination,
+        amount,
+        authority: fundraiser,
+    }
+    .invoke_signed(&[Signer::from(fundraiser_seeds)])
+}
+
+/// Reads the optional unwrap switch of a payout instruction.
+#[inline(always)]
+pub fn wants_unwrap(instruction_data: &[u8]) -> Result<bool, ProgramError> {
+    if instruction_data.is_empty() {
+        return Ok(false);
+    }
+
+    Ok(load_ix_data::<UnwrapIxData>(instruction_data)?.unwrap())
+}
+
+/// Closes a temporary wSOL account owned by `owner` so its whole balance, payout
+/// included, is returned to `owner` as plain SOL.
+#[inline(always)]
+pub fn unwrap_sol(
+    token_account: &AccountInfo,
+    owner: &AccountInfo,
+    mint: &Pubkey,
+) -> ProgramResult {
+    if mint.ne(&NATIVE_MINT) {
+        return Err(FundraiserError::NotNativeMint.into());
+    }
+
+    CloseAccount {
+        account: token_account,
+        destination: owner,
+        authority: owner,
+    }
+    .invoke()
+}
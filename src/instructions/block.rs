@@ -0,0 +1,116 @@
+use crate::helpers::{
+    check_signer, close_pda_account, create_pda_account, load_acc_data_mut_unchecked,
+};
+use crate::state::{BLOCK_SEED, BlockRecordData, FUNDRAISER_SEED, FundraiserData};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::Seed;
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+use pinocchio::{ProgramResult, msg, pubkey};
+
+/// Refuses further contributions from a wallet. Refunds are never blocked.
+pub fn process_block_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [maker, fundraiser, contributor, block_record, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let b_bump = check_block_accounts(maker, fundraiser, contributor, block_record)?;
+    if !block_record.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let b_seed = [b_bump];
+    let seed = [
+        Seed::from(BLOCK_SEED),
+        Seed::from(fundraiser.key()),
+        Seed::from(contributor.key()),
+        Seed::from(&b_seed),
+    ];
+    create_pda_account::<BlockRecordData>(maker, block_record, &seed)?;
+
+    {
+        let data = &mut block_record.try_borrow_mut_data()?;
+        let block_state = load_acc_data_mut_unchecked::<BlockRecordData>(data)?;
+        block_state.set_blocked_at(Clock::get()?.unix_timestamp as u64);
+        block_state.set_bump(b_bump);
+        block_state.add_padding();
+    }
+
+    let data = &mut fundraiser.try_borrow_mut_data()?;
+    let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+    fundraiser_state.set_blocked_count(
+        fundraiser_state
+            .blocked_count()
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    );
+
+    msg!("Contributor blocked");
+
+    Ok(())
+}
+
+/// Lifts a block and returns the record's rent to the maker.
+pub fn process_unblock_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [maker, fundraiser, contributor, block_record, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_block_accounts(maker, fundraiser, contributor, block_record)?;
+    if block_record.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !block_record.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        fundraiser_state.set_blocked_count(fundraiser_state.blocked_count().saturating_sub(1));
+    }
+
+    close_pda_account(block_record, maker)?;
+
+    msg!("Contributor unblocked");
+
+    Ok(())
+}
+
+fn check_block_accounts(
+    maker: &AccountInfo,        // mut signer
+    fundraiser: &AccountInfo,   // mut. seeds = [b"fundraiser", maker.key().as_ref()]
+    contributor: &AccountInfo,  //
+    block_record: &AccountInfo, // mut. seeds = [b"block", fundraiser.key().as_ref(), contributor.key().as_ref()]
+) -> Result<u8, ProgramError> {
+    check_signer(maker)?;
+
+    let seed = &[FUNDRAISER_SEED, maker.key().as_ref()];
+    let (pda_fundraiser, _) = pubkey::find_program_address(seed, &crate::ID);
+    if pda_fundraiser.ne(fundraiser.key()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let seeds: &[&[u8]] = &[BLOCK_SEED, fundraiser.key(), contributor.key()];
+    let (block_record_pda, b_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if block_record_pda.ne(block_record.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    Ok(b_bump)
+}
@@ -0,0 +1,51 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{check_signer, load_acc_data_mut_unchecked};
+use crate::state::{FUNDRAISER_SEED, FundraiserData, FundraiserStatus};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::{ProgramResult, msg, pubkey};
+
+/// Stops the fundraiser. Whatever is still in the vault becomes refundable pro-rata.
+pub fn process_cancel_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        maker,      // signer
+        fundraiser, // mut. seeds = [b"fundraiser", maker.key().as_ref()]
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(maker)?;
+
+    let seed = &[FUNDRAISER_SEED, maker.key().as_ref()];
+    let (pda_fundraiser, _) = pubkey::find_program_address(seed, &crate::ID);
+    if pda_fundraiser.ne(fundraiser.key()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let data = &mut fundraiser.try_borrow_mut_data()?;
+    let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+
+    if fundraiser_state.status()? != FundraiserStatus::Active {
+        return Err(FundraiserError::FundraiserNotActive.into());
+    }
+
+    fundraiser_state.set_status(FundraiserStatus::Cancelled);
+
+    msg!("Fundraiser cancelled");
+
+    Ok(())
+}
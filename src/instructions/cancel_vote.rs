@@ -0,0 +1,191 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{check_signer, create_pda_account, load_acc_data_mut_unchecked};
+use crate::state::{
+    CANCEL_PROPOSAL_SEED, CANCEL_VOTE_SEED, CancelProposalData, ContributorData, FundraiserData,
+    FundraiserStatus, PERCENTAGE_SCALER, VoteRecordData,
+};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::Seed;
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+use pinocchio::{ProgramResult, msg, pubkey};
+
+/// Opens a cancellation proposal for the fundraiser and counts the proposer's weight.
+pub fn process_propose_cancel_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [contributor, fundraiser, _, cancel_proposal, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(contributor)?;
+
+    let seeds: &[&[u8]] = &[CANCEL_PROPOSAL_SEED, fundraiser.key()];
+    let (cancel_proposal_pda, p_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if cancel_proposal_pda.ne(cancel_proposal.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !cancel_proposal.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let p_seed = [p_bump];
+    let seed = [
+        Seed::from(CANCEL_PROPOSAL_SEED),
+        Seed::from(fundraiser.key()),
+        Seed::from(&p_seed),
+    ];
+    create_pda_account::<CancelProposalData>(contributor, cancel_proposal, &seed)?;
+
+    {
+        let data = &mut cancel_proposal.try_borrow_mut_data()?;
+        let proposal_state = load_acc_data_mut_unchecked::<CancelProposalData>(data)?;
+        proposal_state.set_proposer(contributor.key());
+        proposal_state.set_votes(0);
+        proposal_state.set_created_at(Clock::get()?.unix_timestamp as u64);
+        proposal_state.set_bump(p_bump);
+        proposal_state.add_padding();
+    }
+
+    msg!("Cancellation proposed");
+
+    record_cancel_vote(accounts)
+}
+
+/// Adds the caller's weight to an open cancellation proposal.
+pub fn process_vote_cancel_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [contributor, fundraiser, _, cancel_proposal, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(contributor)?;
+
+    let seeds: &[&[u8]] = &[CANCEL_PROPOSAL_SEED, fundraiser.key()];
+    let (cancel_proposal_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if cancel_proposal_pda.ne(cancel_proposal.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if cancel_proposal.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !cancel_proposal.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    record_cancel_vote(accounts)
+}
+
+fn record_cancel_vote(accounts: &[AccountInfo]) -> ProgramResult {
+    let [
+        contributor,         // mut signer
+        fundraiser,          // mut
+        contributor_account, // mut. seeds = [b"contributor", fundraiser.key().as_ref(), contributor.key().as_ref()]
+        cancel_proposal,     // mut. seeds = [b"cancel", fundraiser.key().as_ref()]
+        cancel_vote_record, // mut init. seeds = [b"cancel_vote", fundraiser.key().as_ref(), contributor.key().as_ref()]
+        _system_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // validate contributor_account PDA
+    let seeds: &[&[u8]] = &[b"contributor", fundraiser.key(), contributor.key()];
+    let (contributor_account_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if contributor_account_pda.ne(contributor_account.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !contributor_account.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if !contributor_account.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+
+    // validate cancel_vote_record PDA, its existence means the contributor already voted
+    let seeds: &[&[u8]] = &[CANCEL_VOTE_SEED, fundraiser.key(), contributor.key()];
+    let (cancel_vote_pda, v_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if cancel_vote_pda.ne(cancel_vote_record.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !cancel_vote_record.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    // cancellation votes count for as long as the fundraiser is active, so the weight stays
+    // with this wallet for good, a refund takes it back out of the proposal
+    let weight = {
+        let data = &mut contributor_account.try_borrow_mut_data()?;
+        let contributor_state = load_acc_data_mut_unchecked::<ContributorData>(data)?;
+        contributor_state.lock_until(u64::MAX);
+        contributor_state.amount()
+    };
+    if weight == 0 {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    let votes = {
+        let data = &mut cancel_proposal.try_borrow_mut_data()?;
+        let proposal_state = load_acc_data_mut_unchecked::<CancelProposalData>(data)?;
+        let votes = proposal_state
+            .votes()
+            .checked_add(weight)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        proposal_state.set_votes(votes);
+        votes
+    };
+
+    {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+
+        if fundraiser_state.status()? != FundraiserStatus::Active {
+            return Err(FundraiserError::FundraiserNotActive.into());
+        }
+        if fundraiser_state.cancel_threshold() == 0 {
+            return Err(FundraiserError::CancellationDisabled.into());
+        }
+
+        // sponsors' matches do not vote, so the threshold applies to what contributors put in
+        let required = fundraiser_state.cancel_threshold() as u128
+            * fundraiser_state.contributed_amount() as u128;
+        if votes as u128 * PERCENTAGE_SCALER as u128 >= required {
+            fundraiser_state.set_status(FundraiserStatus::Cancelled);
+            msg!("Fundraiser cancelled by contributors");
+        }
+    }
+
+    let v_seed = [v_bump];
+    let seed = [
+        Seed::from(CANCEL_VOTE_SEED),
+        Seed::from(fundraiser.key()),
+        Seed::from(contributor.key()),
+        Seed::from(&v_seed),
+    ];
+    create_pda_account::<VoteRecordData>(contributor, cancel_vote_record, &seed)?;
+
+    let data = &mut cancel_vote_record.try_borrow_mut_data()?;
+    let vote_state = load_acc_data_mut_unchecked::<VoteRecordData>(data)?;
+    vote_state.set_weight(weight);
+    vote_state.set_tranche(0);
+    vote_state.set_approve(true);
+    vote_state.add_padding();
+
+    msg!("Cancellation vote recorded");
+
+    Ok(())
+}
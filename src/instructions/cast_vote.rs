@@ -0,0 +1,263 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{check_signer, create_pda_account, load_acc_data_mut_unchecked, load_ix_data};
+use crate::state::{
+    CastVoteIxData, ContributorData, FundraiserData, FundraiserStatus, MILESTONES_SEED,
+    MilestonesData, VOTE_SEED, VoteRecordData,
+};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::Seed;
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+use pinocchio::{ProgramResult, msg, pubkey};
+
+pub fn process_cast_vote_instruction(
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        contributor,         // mut signer
+        fundraiser,          //
+        milestones,          // mut. seeds = [b"milestones", fundraiser.key().as_ref()]
+        contributor_account, // mut. seeds = [b"contributor", fundraiser.key().as_ref(), contributor.key().as_ref()]
+        vote_record, // mut init. seeds = [b"vote", fundraiser.key().as_ref(), contributor.key().as_ref(), &[tranche]]
+        _system_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(contributor)?;
+
+    let ix_data = load_ix_data::<CastVoteIxData>(instruction_data)?;
+
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // validate milestones PDA
+    let seed = &[MILESTONES_SEED, fundraiser.key().as_ref()];
+    let (pda_milestones, _) = pubkey::find_program_address(seed, &crate::ID);
+    if pda_milestones.ne(milestones.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !milestones.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+    if !milestones.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // validate contributor_account PDA
+    let seeds: &[&[u8]] = &[b"contributor", fundraiser.key(), contributor.key()];
+    let (contributor_account_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if contributor_account_pda.ne(contributor_account.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !contributor_account.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if !contributor_account.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+
+    // validate vote_record PDA
+    let tranche_seed = [ix_data.tranche()];
+    let seeds: &[&[u8]] = &[
+        VOTE_SEED,
+        fundraiser.key(),
+        contributor.key(),
+        &tranche_seed,
+    ];
+    let (vote_record_pda, v_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if vote_record_pda.ne(vote_record.key()) || ix_data.bump() != v_bump {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // a record left by an earlier attempt of a re-opened tranche vote is cast again
+    let recast = !vote_record.data_is_empty();
+    if recast && !vote_record.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        if fundraiser_state.status()? != FundraiserStatus::Active {
+            return Err(FundraiserError::FundraiserNotActive.into());
+        }
+    }
+
+    let weight = {
+        let data = &mut contributor_account.try_borrow_mut_data()?;
+        load_acc_data_mut_unchecked::<ContributorData>(data)?.amount()
+    };
+    if weight == 0 {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    let (window_end, attempt) = {
+        let data = &mut milestones.try_borrow_mut_data()?;
+        let milestones_state = load_acc_data_mut_unchecked::<MilestonesData>(data)?;
+
+        // only the next pending tranche can be voted on, and only inside its window
+        if !milestones_state.requires_vote()
+            || ix_data.tranche() != milestones_state.next_tranche()
+            || ix_data.tranche() >= milestones_state.count()
+        {
+            return Err(FundraiserError::VotingClosed.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp as u64;
+        let (window_start, window_end) = milestones_state.voting_window(ix_data.tranche());
+        if now < window_start || now >= window_end {
+            return Err(FundraiserError::VotingClosed.into());
+        }
+
+        let tranche = &mut milestones_state.tranches[ix_data.tranche() as usize];
+        if recast {
+            let data = &mut vote_record.try_borrow_mut_data()?;
+            let vote_state = load_acc_data_mut_unchecked::<VoteRecordData>(data)?;
+            if vote_state.attempt() == tranche.attempt() {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+        }
+        if ix_data.approve() {
+            tranche.set_votes_for(
+                tranche
+                    .votes_for()
+                    .checked_add(weight)
+                    .ok_or(ProgramError::ArithmeticOverflow)?,
+            );
+        } else {
+            tranche.set_votes_against(
+                tranche
+                    .votes_against()
+                    .checked_add(weight)
+                    .ok_or(ProgramError::ArithmeticOverflow)?,
+            );
+        }
+
+        (window_end, tranche.attempt())
+    };
+
+    // the weight must not be voted with again from another wallet in the same window
+    {
+        let data = &mut contributor_account.try_borrow_mut_data()?;
+        load_acc_data_mut_unchecked::<ContributorData>(data)?.lock_until(window_end);
+    }
+
+    let v_seed = [v_bump];
+    let seed = [
+        Seed::from(VOTE_SEED),
+        Seed::from(fundraiser.key()),
+        Seed::from(contributor.key()),
+        Seed::from(&tranche_seed),
+        Seed::from(&v_seed),
+    ];
+    if !recast {
+        create_pda_account::<VoteRecordData>(contributor, vote_record, &seed)?;
+    }
+
+    let data = &mut vote_record.try_borrow_mut_data()?;
+    let vote_state = load_acc_data_mut_unchecked::<VoteRecordData>(data)?;
+    vote_state.set_weight(weight);
+    vote_state.set_tranche(ix_data.tranche());
+    vote_state.set_approve(ix_data.approve());
+    vote_state.set_attempt(attempt);
+    vote_state.add_padding();
+
+    msg!("Vote recorded");
+
+    Ok(())
+}
+
+/// Re-opens the vote on the next tranche after contributors rejected it or the quorum was
+/// not reached. The votes are cleared, a new voting window starts now and every
+/// contributor can vote again. Contributors who do not want to fund the rest of the
+/// raise can still propose to cancel it and get back what is left in the vault.
+pub fn process_reopen_vote_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        maker,      // signer
+        fundraiser, //
+        milestones, // mut. seeds = [b"milestones", fundraiser.key().as_ref()]
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(maker)?;
+
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // validate milestones PDA
+    let seed = &[MILESTONES_SEED, fundraiser.key().as_ref()];
+    let (pda_milestones, _) = pubkey::find_program_address(seed, &crate::ID);
+    if pda_milestones.ne(milestones.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !milestones.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+    if !milestones.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let contributed = {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        if fundraiser_state.maker().ne(maker.key()) {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+        if fundraiser_state.status()? != FundraiserStatus::Active {
+            return Err(FundraiserError::FundraiserNotActive.into());
+        }
+        fundraiser_state.contributed_amount()
+    };
+
+    let data = &mut milestones.try_borrow_mut_data()?;
+    let milestones_state = load_acc_data_mut_unchecked::<MilestonesData>(data)?;
+
+    let index = milestones_state.next_tranche();
+    if !milestones_state.requires_vote() || index >= milestones_state.count() {
+        return Err(FundraiserError::VotingClosed.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    let (window_start, window_end) = milestones_state.voting_window(index);
+    if now < window_start {
+        return Err(FundraiserError::TrancheLocked.into());
+    }
+    if now < window_end {
+        return Err(FundraiserError::VotingInProgress.into());
+    }
+    if milestones_state.vote_passed(index, contributed) {
+        return Err(FundraiserError::VotePassed.into());
+    }
+
+    let tranche = &mut milestones_state.tranches[index as usize];
+    tranche.set_attempt(
+        tranche
+            .attempt()
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    );
+    tranche.set_votes_for(0);
+    tranche.set_votes_against(0);
+    tranche.set_unlock_time(now);
+
+    msg!("Vote re-opened");
+
+    Ok(())
+}
@@ -1,7 +1,14 @@
-use crate::helpers::{DataLen, check_signer, load_acc_data_mut_unchecked, load_ix_data};
+use crate::errors::FundraiserError;
+use crate::helpers::{
+    DataLen, check_native_vault, check_signer, check_token_vault, close_pda_account,
+    load_acc_data_mut_unchecked, load_ix_data, transfer_from_vault, unwrap_sol, vault_balance,
+    wants_unwrap,
+};
 use crate::state::{
-    FUNDRAISER_SEED, FundraiserData, InitializeFundraiserIxData, MIN_AMOUNT_TO_RAISE,
-    SECONDS_PER_DAY,
+    ACCEPTED_MINTS_SEED, ALLOCATIONS_SEED, AcceptedMintsData, AllocationsData, FLAG_ALLOCATION,
+    FLAG_MILESTONES, FLAG_MULTI_MINT, FLAG_NATIVE, FLAG_PLEDGE, FLAG_REWARD, FLAG_VESTING,
+    FUNDRAISER_SEED, FundraiserData, FundraiserStatus, InitializeFundraiserIxData,
+    MIN_AMOUNT_TO_RAISE, SECONDS_PER_DAY,
 };
 use pinocchio::account_info::AccountInfo;
 use pinocchio::instruction::Seed;
@@ -33,7 +40,8 @@ pub fn process_check_contributions_instruction(
         system_program,
         associated_token_program,
         rent_sysvar,
-        remaining @ ..,
+        remaining @ .., // accepted mints PDA, then a (vault, maker ATA) pair per extra mint when FLAG_MULTI_MINT is set;
+                        // allocations PDA, then a token account per beneficiary when FLAG_ALLOCATION is set
     ] = accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
@@ -62,13 +70,85 @@ pub fn process_check_contributions_instruction(
     }
 
     // Access fundraiser account data to pick amount to raise value
-    let data = &mut fundraiser.try_borrow_mut_data()?;
-    let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+    let unwrap = wants_unwrap(instruction_data)?;
 
-    let vault_token_acc = TokenAccount::from_account_info(vault).unwrap();
-    if vault_token_acc.amount() < fundraiser_state.amount_to_raise() {
-        return Err(ProgramError::Custom(0));
-    }
+    let (amount, vesting, native, multi_mint, reward, allocation, mint) = {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+
+        if fundraiser_state.status()? != FundraiserStatus::Active {
+            return Err(FundraiserError::FundraiserNotActive.into());
+        }
+
+        let native = fundraiser_state.has_flag(FLAG_NATIVE);
+        let multi_mint = fundraiser_state.has_flag(FLAG_MULTI_MINT);
+        if native {
+            check_native_vault(fundraiser, vault)?;
+        } else {
+            check_token_vault(fundraiser, vault, &fundraiser_state.mint_to_raise())?;
+        }
+        let vault_amount = vault_balance(vault, native)?;
+
+        // Milestone campaigns release the vault through ClaimTranche only
+        if fundraiser_state.has_flag(FLAG_MILESTONES) {
+            return Err(FundraiserError::InvalidReleaseMode.into());
+        }
+
+        if fundraiser_state.has_flag(FLAG_VESTING) {
+            if !fundraiser_state.is_target_met() {
+                return Err(FundraiserError::TargetNotMet.into());
+            }
+
+            // the schedule releases what was raised by the deadline, not the live total
+            let now = Clock::get()?.unix_timestamp as u64;
+            if now >= fundraiser_state.deadline() && fundraiser_state.vesting_base() == 0 {
+                fundraiser_state.set_vesting_base(fundraiser_state.current_amount());
+            }
+            let released = fundraiser_state.released_amount();
+            let claimable = fundraiser_state
+                .vested_amount(now)?
+                .saturating_sub(released);
+            if claimable == 0 {
+                return Err(FundraiserError::NothingToClaim.into());
+            }
+
+            fundraiser_state.set_released_amount(released + claimable);
+            (
+                claimable,
+                released + claimable < fundraiser_state.vesting_base(),
+                native,
+                multi_mint,
+                fundraiser_state.has_flag(FLAG_REWARD),
+                false,
+                fundraiser_state.mint_to_raise(),
+            )
+        } else {
+            if fundraiser_state.has_flag(FLAG_PLEDGE) {
+                // the pledges met the target, the maker gets whatever could be collected
+                if !fundraiser_state.is_target_met() {
+                    return Err(FundraiserError::TargetNotMet.into());
+                }
+                if !fundraiser_state.pledges_collected()? {
+                    return Err(FundraiserError::PledgesNotCollected.into());
+                }
+            } else if fundraiser_state.is_normalized() && !fundraiser_state.is_target_met()
+                || !fundraiser_state.is_normalized()
+                    && vault_amount < fundraiser_state.amount_to_raise()
+            {
+                // a normalized target is met by the credited amounts, not by the vault balance
+                return Err(FundraiserError::TargetNotMet.into());
+            }
+            (
+                vault_amount,
+                false,
+                native,
+                multi_mint,
+                fundraiser_state.has_flag(FLAG_REWARD),
+                fundraiser_state.has_flag(FLAG_ALLOCATION),
+                fundraiser_state.mint_to_raise(),
+            )
+        }
+    };
 
     let f_bump_seed = [f_bump.to_le()];
     let s_seed = [
@@ -77,35 +157,143 @@ pub fn process_check_contributions_instruction(
         Seed::from(&f_bump_seed),
     ];
 
-    let signer_seeds = Signer::from(&s_seed);
-    pinocchio_token::instructions::Transfer {
-        from: &vault,
-        to: &maker_ata,
-        amount: vault_token_acc.amount(),
-        authority: &fundraiser,
+    // Beneficiaries are paid what donors allocated to them, the maker gets the rest
+    let mut amount = amount;
+    if allocation {
+        let [allocations, beneficiary_atas @ ..] = remaining else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let seed = &[ALLOCATIONS_SEED, fundraiser.key().as_ref()];
+        let (pda_allocations, _) = pubkey::find_program_address(seed, &crate::ID);
+        if pda_allocations.ne(allocations.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !allocations.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let allocations_state = {
+            let data = &mut allocations.try_borrow_mut_data()?;
+            *load_acc_data_mut_unchecked::<AllocationsData>(data)?
+        };
+
+        let count = allocations_state.count() as usize;
+        if beneficiary_atas.len() < count {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        for (beneficiary, beneficiary_ata) in allocations_state.beneficiaries[..count]
+            .iter()
+            .zip(beneficiary_atas.iter())
+        {
+            {
+                let token_account = TokenAccount::from_account_info(beneficiary_ata)?;
+                if token_account.owner().ne(&beneficiary.wallet()) || token_account.mint().ne(&mint)
+                {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+            }
+
+            if beneficiary.total() == 0 {
+                continue;
+            }
+            amount = amount
+                .checked_sub(beneficiary.total())
+                .ok_or(ProgramError::InsufficientFunds)?;
+            transfer_from_vault(
+                vault,
+                beneficiary_ata,
+                fundraiser,
+                &s_seed,
+                beneficiary.total(),
+                false,
+            )?;
+        }
+
+        close_pda_account(allocations, maker)?;
     }
-    .invoke_signed(&[signer_seeds])?;
-
-    // let s = &[FUNDRAISER_SEED.as_ref(), maker.key()];
-    // pinocchio_system::instructions::TransferWithSeed {
-    //     from: fundraiser,
-    //     base: fundraiser,
-    //     to: maker,
-    //     lamports: fundraiser.lamports(),
-    //     seed: seed.as_ptr(),
-    //     owner: &crate::ID,
-    // }
-    // .invoke_signed(&[signer_seeds])?;
-
-    let signer_seeds1 = Signer::from(&s_seed);
-    pinocchio_system::instructions::Transfer {
-        from: fundraiser,
-        to: maker,
-        lamports: fundraiser.lamports(),
+
+    let destination = if native { maker } else { maker_ata };
+    transfer_from_vault(vault, destination, fundraiser, &s_seed, amount, native)?;
+
+    if unwrap && !native {
+        unwrap_sol(maker_ata, maker, &mint)?;
+    }
+
+    // The other accepted mints are paid out of their own vaults in the same claim
+    if multi_mint {
+        let [accepted_mints, pairs @ ..] = remaining else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let seed = &[ACCEPTED_MINTS_SEED, fundraiser.key().as_ref()];
+        let (pda_accepted_mints, _) = pubkey::find_program_address(seed, &crate::ID);
+        if pda_accepted_mints.ne(accepted_mints.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !accepted_mints.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let accepted_state = {
+            let data = &mut accepted_mints.try_borrow_mut_data()?;
+            *load_acc_data_mut_unchecked::<AcceptedMintsData>(data)?
+        };
+
+        // one (vault, maker token account) pair per registered mint after the first
+        let count = accepted_state.count() as usize;
+        if pairs.len() < 2 * (count - 1) {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        for (entry, pair) in accepted_state.mints[1..count]
+            .iter()
+            .zip(pairs.chunks_exact(2))
+        {
+            let [mint_vault, maker_mint_ata] = pair else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            let vault_amount = {
+                let vault_account = TokenAccount::from_account_info(mint_vault)?;
+                if vault_account.owner().ne(fundraiser.key())
+                    || vault_account.mint().ne(&entry.mint())
+                {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                vault_account.amount()
+            };
+
+            transfer_from_vault(
+                mint_vault,
+                maker_mint_ata,
+                fundraiser,
+                &s_seed,
+                vault_amount,
+                false,
+            )?;
+        }
+
+        close_pda_account(accepted_mints, maker)?;
+    }
+
+    // Vesting fundraisers stay open until the last claim of the schedule
+    if vesting {
+        return Ok(());
     }
-    .invoke_signed(&[signer_seeds1])?;
 
-    fundraiser.close()?;
+    if reward {
+        // Contributors still need the raise totals to claim their rewards
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        load_acc_data_mut_unchecked::<FundraiserData>(data)?
+            .set_status(FundraiserStatus::Succeeded);
+    } else {
+        close_pda_account(fundraiser, maker)?;
+    }
+
+    // The native vault only holds its rent-exempt reserve by now
+    if native {
+        close_pda_account(vault, maker)?;
+    }
 
     Ok(())
 }
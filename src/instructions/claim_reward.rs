@@ -0,0 +1,235 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{check_signer, close_pda_account, load_acc_data_mut_unchecked, mul_div};
+use crate::state::{
+    ContributorData, FLAG_REWARD, FUNDRAISER_SEED, FundraiserData, FundraiserStatus,
+};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::{Seed, Signer};
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+use pinocchio::{ProgramResult, msg, pubkey};
+use pinocchio_token::instructions::{CloseAccount, Transfer};
+use pinocchio_token::state::TokenAccount;
+
+/// Pays a contributor of a successful fundraiser their share of the reward vault.
+///
+/// Contributions are weighed by their shares, which include any early-bird bonus.
+/// The vault balance and the total shares are fixed by the first claim, so every
+/// contributor is paid from the same totals. Rewards sent to the vault after that
+/// are not distributed. Once every share has been claimed, the maker can close the
+/// fundraiser with CloseRewards.
+pub fn process_claim_reward_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        contributor,         // mut signer
+        fundraiser,          // mut
+        contributor_account, // mut. seeds = [b"contributor", fundraiser.key().as_ref(), contributor.key().as_ref()]
+        reward_vault,        // mut ata. authority = fundraiser
+        contributor_reward,  // mut. contributor's token account of the reward mint
+        _token_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(contributor)?;
+
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // validate contributor_account PDA
+    let seeds: &[&[u8]] = &[b"contributor", fundraiser.key(), contributor.key()];
+    let (contributor_account_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if contributor_account_pda.ne(contributor_account.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !contributor_account.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if !contributor_account.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+
+    let (vault_amount, vault_mint) = {
+        let vault_account = TokenAccount::from_account_info(reward_vault)?;
+        if vault_account.owner().ne(fundraiser.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        (vault_account.amount(), *vault_account.mint())
+    };
+
+    let (reward_total, reward_basis, maker, f_bump) = {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+
+        if !fundraiser_state.has_flag(FLAG_REWARD) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if vault_mint.ne(&fundraiser_state.reward_mint()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if !fundraiser_state.has_succeeded(Clock::get()?.unix_timestamp as u64)? {
+            return Err(FundraiserError::NotSucceeded.into());
+        }
+
+        if fundraiser_state.reward_basis() == 0 {
+            if vault_amount == 0 {
+                return Err(FundraiserError::NothingToClaim.into());
+            }
+            fundraiser_state.set_reward_total(vault_amount);
+            fundraiser_state.set_reward_basis(fundraiser_state.total_shares());
+        }
+
+        (
+            fundraiser_state.reward_total(),
+            fundraiser_state.reward_basis(),
+            fundraiser_state.maker(),
+            fundraiser_state.bump(),
+        )
+    };
+
+    let (reward, shares) = {
+        let data = &mut contributor_account.try_borrow_mut_data()?;
+        let contributor_state = load_acc_data_mut_unchecked::<ContributorData>(data)?;
+
+        if contributor_state.reward_claimed() {
+            return Err(FundraiserError::RewardAlreadyClaimed.into());
+        }
+        contributor_state.set_reward_claimed(true);
+
+        let shares = contributor_state.shares();
+        (mul_div(reward_total, shares, reward_basis)?, shares)
+    };
+
+    // a share rounding down to nothing still counts as claimed, so the rewards can close
+
+    {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        let claimed = fundraiser_state
+            .reward_claimed_shares()
+            .checked_add(shares)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        fundraiser_state.set_reward_claimed_shares(claimed);
+    }
+
+    let f_seed = [f_bump];
+    let seed = [
+        Seed::from(FUNDRAISER_SEED),
+        Seed::from(&maker),
+        Seed::from(&f_seed),
+    ];
+    if reward > 0 {
+        Transfer {
+            from: reward_vault,
+            to: contributor_reward,
+            authority: fundraiser,
+            amount: reward,
+        }
+        .invoke_signed(&[Signer::from(&seed)])?;
+    }
+
+    msg!("Reward claimed");
+
+    Ok(())
+}
+
+/// Closes a succeeded fundraiser once every contributor claimed their reward, or once
+/// `REWARD_CLAIM_PERIOD` has passed since the deadline. Whatever is left in the reward
+/// vault, rounding dust or unclaimed rewards, goes back to the maker with the rent of
+/// both accounts.
+pub fn process_close_rewards_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        maker,        // mut signer
+        fundraiser,   // mut. seeds = [b"fundraiser", maker.key().as_ref()]
+        reward_vault, // mut ata. authority = fundraiser
+        maker_reward, // mut. maker's token account of the reward mint
+        _token_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(maker)?;
+
+    let seed = &[FUNDRAISER_SEED, maker.key().as_ref()];
+    let (pda_fundraiser, f_bump) = pubkey::find_program_address(seed, &crate::ID);
+    if pda_fundraiser.ne(fundraiser.key()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let reward_mint = {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+
+        if !fundraiser_state.has_flag(FLAG_REWARD) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if fundraiser_state.status()? != FundraiserStatus::Succeeded {
+            return Err(FundraiserError::NotSucceeded.into());
+        }
+        if !fundraiser_state.rewards_closable(Clock::get()?.unix_timestamp as u64) {
+            return Err(FundraiserError::RewardsOutstanding.into());
+        }
+        fundraiser_state.reward_mint()
+    };
+
+    let dust = {
+        let vault_account = TokenAccount::from_account_info(reward_vault)?;
+        if vault_account.owner().ne(fundraiser.key()) || vault_account.mint().ne(&reward_mint) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        vault_account.amount()
+    };
+
+    let f_seed = [f_bump];
+    let seed = [
+        Seed::from(FUNDRAISER_SEED),
+        Seed::from(maker.key()),
+        Seed::from(&f_seed),
+    ];
+    if dust > 0 {
+        Transfer {
+            from: reward_vault,
+            to: maker_reward,
+            authority: fundraiser,
+            amount: dust,
+        }
+        .invoke_signed(&[Signer::from(&seed)])?;
+    }
+    CloseAccount {
+        account: reward_vault,
+        destination: maker,
+        authority: fundraiser,
+    }
+    .invoke_signed(&[Signer::from(&seed)])?;
+
+    close_pda_account(fundraiser, maker)?;
+
+    msg!("Rewards closed");
+
+    Ok(())
+}
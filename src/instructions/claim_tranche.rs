@@ -0,0 +1,180 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{
+    check_native_vault, check_signer, check_token_vault, close_pda_account,
+    load_acc_data_mut_unchecked, mul_div, transfer_from_vault,
+};
+use crate::state::{
+    FLAG_MILESTONES, FLAG_NATIVE, FLAG_REWARD, FUNDRAISER_SEED, FundraiserData, FundraiserStatus,
+    MILESTONES_SEED, MilestonesData, PERCENTAGE_SCALER,
+};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::Seed;
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+use pinocchio::{ProgramResult, msg, pubkey};
+
+/// Releases the next tranche of a milestone fundraiser to the maker. Once the target is
+/// met the raise can no longer fail, only be cancelled, which refunds what is still in
+/// the vault, so tranches are not held back until the deadline. The last tranche closes
+/// the fundraiser and its milestones like a completed Check does.
+pub fn process_claim_tranche_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        maker,      // mut signer
+        fundraiser, // mut. seeds = [b"fundraiser", maker.key().as_ref()]
+        milestones, // mut. seeds = [b"milestones", fundraiser.key().as_ref()]
+        vault,      // mut
+        maker_ata,  // mut
+        _token_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(maker)?;
+
+    // validate fundraiser PDA
+    let seed = &[FUNDRAISER_SEED, maker.key().as_ref()];
+    let (pda_fundraiser, f_bump) = pubkey::find_program_address(seed, &crate::ID);
+    if pda_fundraiser.ne(fundraiser.key()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // validate milestones PDA
+    let seed = &[MILESTONES_SEED, fundraiser.key().as_ref()];
+    let (pda_milestones, _) = pubkey::find_program_address(seed, &crate::ID);
+    if pda_milestones.ne(milestones.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !milestones.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+    if !milestones.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let (raised, contributed, released, native, reward, mint) = {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+
+        if fundraiser_state.status()? != FundraiserStatus::Active {
+            return Err(FundraiserError::FundraiserNotActive.into());
+        }
+        if !fundraiser_state.has_flag(FLAG_MILESTONES) {
+            return Err(FundraiserError::InvalidReleaseMode.into());
+        }
+        if !fundraiser_state.is_target_met() {
+            return Err(FundraiserError::TargetNotMet.into());
+        }
+
+        (
+            fundraiser_state.current_amount(),
+            fundraiser_state.contributed_amount(),
+            fundraiser_state.released_amount(),
+            fundraiser_state.has_flag(FLAG_NATIVE),
+            fundraiser_state.has_flag(FLAG_REWARD),
+            fundraiser_state.mint_to_raise(),
+        )
+    };
+
+    if native {
+        check_native_vault(fundraiser, vault)?;
+    } else {
+        check_token_vault(fundraiser, vault, &mint)?;
+    }
+
+    let (tranche_index, last, amount) = {
+        let data = &mut milestones.try_borrow_mut_data()?;
+        let milestones_state = load_acc_data_mut_unchecked::<MilestonesData>(data)?;
+
+        let tranche_index = milestones_state.next_tranche();
+        if tranche_index >= milestones_state.count() {
+            return Err(FundraiserError::NoTranchesRemaining.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp as u64;
+        let unlock_time = milestones_state.tranches[tranche_index as usize].unlock_time();
+        if now < unlock_time {
+            return Err(FundraiserError::TrancheLocked.into());
+        }
+
+        // With governance enabled the tranche is only released once contributors approved it
+        if milestones_state.requires_vote() {
+            let (_, window_end) = milestones_state.voting_window(tranche_index);
+            if now < window_end {
+                return Err(FundraiserError::VotingInProgress.into());
+            }
+            // a rejected tranche stays locked until the maker re-opens its vote
+            if !milestones_state.vote_passed(tranche_index, contributed) {
+                return Err(FundraiserError::VoteNotPassed.into());
+            }
+        }
+
+        // Release up to the cumulative percentage so rounding dust ends up in the last tranche
+        let cumulative = milestones_state.cumulative_percentage(tranche_index);
+        let unlocked = mul_div(raised, cumulative, PERCENTAGE_SCALER)?;
+
+        (
+            tranche_index,
+            tranche_index + 1 == milestones_state.count(),
+            unlocked.saturating_sub(released),
+        )
+    };
+
+    pinocchio_log::log!("releasing tranche {}: {}", tranche_index, amount);
+
+    let f_bump_seed = [f_bump];
+    let seed = [
+        Seed::from(FUNDRAISER_SEED),
+        Seed::from(maker.key()),
+        Seed::from(&f_bump_seed),
+    ];
+    let destination = if native { maker } else { maker_ata };
+    transfer_from_vault(vault, destination, fundraiser, &seed, amount, native)?;
+
+    {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        fundraiser_state.set_released_amount(released + amount);
+    }
+
+    {
+        let data = &mut milestones.try_borrow_mut_data()?;
+        let milestones_state = load_acc_data_mut_unchecked::<MilestonesData>(data)?;
+        milestones_state.set_next_tranche(tranche_index + 1);
+    }
+
+    msg!("Tranche released");
+
+    // Everything has been released, so the raise is over
+    if last {
+        if reward {
+            // Contributors still need the raise totals to claim their rewards
+            let data = &mut fundraiser.try_borrow_mut_data()?;
+            load_acc_data_mut_unchecked::<FundraiserData>(data)?
+                .set_status(FundraiserStatus::Succeeded);
+        } else {
+            close_pda_account(fundraiser, maker)?;
+        }
+        close_pda_account(milestones, maker)?;
+
+        // The native vault only holds its rent-exempt reserve by now
+        if native {
+            close_pda_account(vault, maker)?;
+        }
+    }
+
+    Ok(())
+}
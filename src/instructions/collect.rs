@@ -0,0 +1,173 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{load_acc_data_mut_unchecked, transfer_from_vault};
+use crate::state::{
+    ContributorData, FLAG_PLEDGE, FUNDRAISER_SEED, FundraiserData, FundraiserStatus,
+};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::Seed;
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+use pinocchio::{ProgramResult, msg, pubkey};
+use pinocchio_token::state::TokenAccount;
+
+/// Pulls the pledges of a pledge fundraiser that met its target into the vault, using
+/// the delegate approval each pledger gave the fundraiser. A pledge that cannot be
+/// pulled in full is collected as far as possible and the rest recorded as shortfall,
+/// so a broken pledge never holds up the others. Anyone can collect, in batches.
+pub fn process_collect_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        fundraiser, // mut
+        vault,      // mut
+        _token_program,
+        pledges @ .., // (contributor, contributor PDA, pledge token account) per pledge
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let (maker, mint, f_bump) = {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        if !fundraiser_state.has_flag(FLAG_PLEDGE) {
+            return Err(FundraiserError::InvalidReleaseMode.into());
+        }
+        if fundraiser_state.status()? != FundraiserStatus::Active {
+            return Err(FundraiserError::FundraiserNotActive.into());
+        }
+        if (Clock::get()?.unix_timestamp as u64) < fundraiser_state.deadline() {
+            return Err(FundraiserError::FundraiserNotEnded.into());
+        }
+        if !fundraiser_state.is_target_met() {
+            return Err(FundraiserError::TargetNotMet.into());
+        }
+        (
+            fundraiser_state.maker(),
+            fundraiser_state.mint_to_raise(),
+            fundraiser_state.bump(),
+        )
+    };
+
+    {
+        let vault_account = TokenAccount::from_account_info(vault)?;
+        if vault_account.owner().ne(fundraiser.key()) || vault_account.mint().ne(&mint) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    let f_seed = [f_bump];
+    let seed = [
+        Seed::from(FUNDRAISER_SEED),
+        Seed::from(&maker),
+        Seed::from(&f_seed),
+    ];
+
+    let mut collected: u64 = 0;
+    let mut shortfall: u64 = 0;
+    let mut forfeited_shares: u64 = 0;
+    for pledge in pledges.chunks_exact(3) {
+        let [contributor, contributor_account, pledge_account] = pledge else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let seeds: &[&[u8]] = &[b"contributor", fundraiser.key(), contributor.key()];
+        let (contributor_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+        if contributor_pda.ne(contributor_account.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !contributor_account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let pledged = {
+            let data = &mut contributor_account.try_borrow_mut_data()?;
+            let contributor_state = load_acc_data_mut_unchecked::<ContributorData>(data)?;
+            if contributor_state.collected() {
+                continue;
+            }
+            // only the account approved with the pledge can be pulled from
+            if contributor_state.pledge_account().ne(pledge_account.key()) {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            contributor_state.amount()
+        };
+
+        // a closed, frozen, emptied or revoked account is a failed pledge, not an error
+        let available = match TokenAccount::from_account_info(pledge_account) {
+            Ok(token_account)
+                if token_account.mint().eq(&mint)
+                    && token_account.delegate() == Some(fundraiser.key())
+                    && !token_account.is_frozen() =>
+            {
+                token_account.amount().min(token_account.delegated_amount())
+            }
+            _ => 0,
+        };
+        let pulled = pledged.min(available);
+        if pulled > 0 {
+            transfer_from_vault(pledge_account, vault, fundraiser, &seed, pulled, false)?;
+        }
+
+        let data = &mut contributor_account.try_borrow_mut_data()?;
+        let contributor_state = load_acc_data_mut_unchecked::<ContributorData>(data)?;
+        contributor_state.set_collected(true);
+        if pulled < pledged {
+            // rewards are only shared for what was actually paid in
+            let shares = contributor_state.shares_of(pulled)?;
+            forfeited_shares = contributor_state
+                .shares()
+                .checked_sub(shares)
+                .and_then(|forfeited| forfeited_shares.checked_add(forfeited))
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            contributor_state.set_shares(shares);
+            contributor_state.set_shortfall(
+                pledged
+                    .checked_sub(pulled)
+                    .ok_or(ProgramError::ArithmeticOverflow)?,
+            );
+        }
+
+        collected = collected
+            .checked_add(pulled)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        shortfall = pledged
+            .checked_sub(pulled)
+            .and_then(|missing| shortfall.checked_add(missing))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+
+    {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        fundraiser_state.set_collected_amount(
+            fundraiser_state
+                .collected_amount()
+                .checked_add(collected)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+        fundraiser_state.set_shortfall_amount(
+            fundraiser_state
+                .shortfall_amount()
+                .checked_add(shortfall)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+        fundraiser_state.set_total_shares(
+            fundraiser_state
+                .total_shares()
+                .saturating_sub(forfeited_shares),
+        );
+    }
+
+    msg!("Pledges collected");
+
+    Ok(())
+}
@@ -1,7 +1,16 @@
-use crate::helpers::{DataLen, check_signer, load_acc_data_mut_unchecked, load_ix_data};
+use crate::errors::FundraiserError;
+use crate::helpers::{
+    DataLen, OraclePrice, check_allowlist_proof, check_attestation, check_matching_pool,
+    check_native_vault, check_not_blocked, check_receipt_mint, check_signer, check_token_gate,
+    load_acc_data_mut_unchecked, record_in_campaign, split_ix_data, update_round_weight,
+};
 use crate::state::{
-    ContributeIxData, ContributorData, FUNDRAISER_SEED, FundraiserData, InitializeFundraiserIxData,
-    MAX_CONTRIBUTION_PERCENTAGE, MIN_AMOUNT_TO_RAISE, PERCENTAGE_SCALER, SECONDS_PER_DAY,
+    ACCEPTED_MINTS_SEED, ALLOCATIONS_SEED, AcceptedMintsData, AllocationIxData, AllocationsData,
+    ContributeIxData, ContributorData, FLAG_ALLOCATION, FLAG_ALLOWLIST, FLAG_ATTESTATION,
+    FLAG_CAMPAIGN, FLAG_MATCHING, FLAG_MULTI_MINT, FLAG_NATIVE, FLAG_ORACLE, FLAG_PLEDGE,
+    FLAG_RECEIPT, FLAG_TIERS, FLAG_TOKEN_GATE, FUNDRAISER_SEED, FundraiserData, FundraiserStatus,
+    InitializeFundraiserIxData, MATCHING_SEED, MAX_CONTRIBUTION_PERCENTAGE, MIN_AMOUNT_TO_RAISE,
+    MatchingPoolData, NATIVE_MINT, PERCENTAGE_SCALER, SECONDS_PER_DAY, TIERS_SEED, TiersData,
 };
 use pinocchio::account_info::AccountInfo;
 use pinocchio::instruction::Seed;
@@ -14,7 +23,7 @@ use pinocchio::{ProgramResult, msg, pubkey::find_program_address};
 //use pinocchio_associated_token_account::solana_program;
 use pinocchio_associated_token_account::instructions::Create as Create_ATA;
 use pinocchio_system::instructions::{CreateAccount, CreateAccountWithSeed};
-use pinocchio_token::instructions::InitializeAccount;
+use pinocchio_token::instructions::{InitializeAccount, MintTo, SyncNative};
 use pinocchio_token::state::{Mint, TokenAccount};
 //use spl_token::solana_program::program_pack::Pack;
 use crate::helpers::create_pda_account;
@@ -35,6 +44,12 @@ pub fn process_contribute_instruction(
         system_program,
         associated_token_program,
         rent_sysvar,
+        // in order: accepted mints PDA (FLAG_MULTI_MINT) or price account (FLAG_ORACLE),
+        // gate token account (FLAG_TOKEN_GATE), block record PDA (once anyone is blocked),
+        // attestation PDA (FLAG_ATTESTATION), tiers PDA (when a tier is selected), matching
+        // pool PDA and its vault (FLAG_MATCHING), round PDA (once the fundraiser joined a
+        // round), campaign PDA (FLAG_CAMPAIGN), allocations PDA (FLAG_ALLOCATION), receipt
+        // mint PDA and the contributor's receipt token account (FLAG_RECEIPT)
         remaining @ ..,
     ] = accounts
     else {
@@ -69,7 +84,7 @@ pub fn process_contribute_instruction(
         return Err(ProgramError::Immutable);
     }
 
-    let ix_data = load_ix_data::<ContributeIxData>(&instruction_data)?;
+    let (ix_data, extra_data) = split_ix_data::<ContributeIxData>(&instruction_data)?;
 
     let seeds: &[&[u8]] = &[
         b"contributor",   //.as_ref(),
@@ -112,64 +127,271 @@ pub fn process_contribute_instruction(
     //     return Err(ProgramError::InvalidSeeds);
     // }
 
-    if !contributor_ata.is_writable() {
-        return Err(ProgramError::Immutable);
+    let native = {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        load_acc_data_mut_unchecked::<FundraiserData>(data)?.has_flag(FLAG_NATIVE)
+    };
+
+    // check amount is within range
+    let mut decimals: u8 = 0;
+
+    if native {
+        // lamports go straight into the program owned vault PDA, no token accounts involved
+        check_native_vault(fundraiser, vault)?;
+        decimals = 9;
+    } else {
+        // wrapped contributions are paid in lamports, the contributor ATA is not used
+        if ix_data.wrap() && mint_to_raise.key().ne(&NATIVE_MINT) {
+            return Err(FundraiserError::NotNativeMint.into());
+        }
+
+        if !ix_data.wrap() && !contributor_ata.is_writable() {
+            return Err(ProgramError::Immutable);
+        }
+
+        pinocchio_log::log!("contributor ATA owner {}", contributor_ata.owner());
+        pinocchio_log::log!("&token_program.key() {}", token_program.key());
+
+        // if !contributor_ata.is_owned_by(&token_program.key()) {
+        //     return Err(ProgramError::IllegalOwner);
+        // }
+        if !ix_data.wrap() && contributor_ata.owner() != token_program.key() {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        msg!("contributor ATA verified");
+        // check vault_ata PDA validity and mutability
+        if !vault.is_writable() {
+            return Err(ProgramError::Immutable);
+        }
+
+        if !vault.is_owned_by(&token_program.key()) {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        {
+            let vault_account = TokenAccount::from_account_info(vault)?;
+            if vault_account.owner().ne(fundraiser.key())
+                || vault_account.mint().ne(mint_to_raise.key())
+            {
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+
+        msg!("vault verified.");
+
+        // Access mint account to retrieve decimals
+        // Try to parse as TokenAccount
+        // let m = Mint::from_account_info(mint_to_raise).unwrap();
+        match Mint::from_account_info(mint_to_raise) {
+            Ok(m_account) => {
+                if !m_account.is_initialized() {
+                    return Err(pinocchio::program_error::UNINITIALIZED_ACCOUNT.into());
+                }
+                decimals = m_account.decimals();
+            }
+            Err(e) => {
+                return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+            }
+        }
+
+        msg!("mint deserialized  data");
     }
 
-    pinocchio_log::log!("contributor ATA owner {}", contributor_ata.owner());
-    pinocchio_log::log!("&token_program.key() {}", token_program.key());
+    // Access fundraiser account data to pick amount to raise value
+    let mut data = fundraiser.try_borrow_mut_data()?;
+    let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(&mut data)?;
 
-    // if !contributor_ata.is_owned_by(&token_program.key()) {
-    //     return Err(ProgramError::IllegalOwner);
-    // }
-    if contributor_ata.owner() != token_program.key() {
-        return Err(ProgramError::IllegalOwner);
+    if fundraiser_state.status()? != FundraiserStatus::Active {
+        return Err(FundraiserError::FundraiserNotActive.into());
     }
 
-    msg!("contributor ATA verified");
-    // check vault_ata PDA validity and mutability
-    if !vault.is_writable() {
-        return Err(ProgramError::Immutable);
+    let multi_mint = fundraiser_state.has_flag(FLAG_MULTI_MINT);
+    if !native && !multi_mint && fundraiser_state.mint_to_raise().ne(mint_to_raise.key()) {
+        return Err(ProgramError::InvalidAccountData);
     }
 
-    if !vault.is_owned_by(&token_program.key()) {
-        return Err(ProgramError::IllegalOwner);
+    // Releases, vesting and pledge collection work from what was raised by the deadline,
+    // so nothing can be added after it
+    if Clock::get()?.unix_timestamp as u64 >= fundraiser_state.deadline() {
+        return Err(FundraiserError::FundraiserNotActive.into());
     }
 
-    msg!("vault verified.");
+    let pledge = fundraiser_state.has_flag(FLAG_PLEDGE);
+    if pledge && ix_data.wrap() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
 
-    // check amount is within range
-    let mut decimals: u8 = 0;
+    let amount = ix_data.amount();
+    let mut extra_data = extra_data;
+
+    // Private rounds only accept contributors proving they are in the allowlist
+    let mut cap = 0;
+    if fundraiser_state.has_flag(FLAG_ALLOWLIST) {
+        let (proof_cap, rest) = check_allowlist_proof(
+            contributor.key(),
+            &fundraiser_state.merkle_root(),
+            extra_data,
+        )?;
+        extra_data = rest;
+        cap = proof_cap;
+    }
 
-    // Access mint account to retrieve decimals
-    // Try to parse as TokenAccount
-    // let m = Mint::from_account_info(mint_to_raise).unwrap();
-    match Mint::from_account_info(mint_to_raise) {
-        Ok(m_account) => {
-            if !m_account.is_initialized() {
-                return Err(pinocchio::program_error::UNINITIALIZED_ACCOUNT.into());
-            }
-            decimals = m_account.decimals();
+    // Donors of donor-directed raises say how their gift is split among the beneficiaries
+    let mut allocation = None;
+    if fundraiser_state.has_flag(FLAG_ALLOCATION) {
+        let (allocation_ix, rest) = split_ix_data::<AllocationIxData>(extra_data)?;
+        extra_data = rest;
+        allocation = Some(allocation_ix);
+    }
+
+    if !extra_data.is_empty() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let mut remaining = remaining.iter();
+
+    // Multi-mint fundraisers credit contributions in their normalized unit
+    let (mint_index, credited) = if multi_mint {
+        let accepted_mints = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let seed = &[ACCEPTED_MINTS_SEED, fundraiser.key().as_ref()];
+        let (pda_accepted_mints, _) = find_program_address(seed, program_id);
+        if pda_accepted_mints.ne(accepted_mints.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !accepted_mints.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IllegalOwner);
         }
-        Err(e) => {
-            return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+
+        let data = &mut accepted_mints.try_borrow_mut_data()?;
+        let accepted_state = load_acc_data_mut_unchecked::<AcceptedMintsData>(data)?;
+        let index = accepted_state
+            .find(mint_to_raise.key())
+            .ok_or(FundraiserError::MintNotAccepted)?;
+
+        let entry = &mut accepted_state.mints[index];
+        entry.set_deposited(
+            entry
+                .deposited()
+                .checked_add(amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+        (Some(index), entry.to_normalized(amount)?)
+    } else if fundraiser_state.has_flag(FLAG_ORACLE) {
+        // Oracle priced fundraisers credit the USD value of the contribution
+        let price_account = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if price_account.key().ne(&fundraiser_state.price_account()) {
+            return Err(ProgramError::InvalidAccountData);
         }
+
+        let price = OraclePrice::from_bytes(&price_account.try_borrow_data()?)?;
+        price.check(Clock::get()?.unix_timestamp)?;
+        (None, price.usd_value(amount, decimals)?)
+    } else {
+        (None, amount)
+    };
+
+    if credited == 0 {
+        return Err(ProgramError::InvalidInstructionData);
     }
 
-    msg!("mint deserialized  data");
+    // Gated fundraisers only accept holders of enough of the gate mint
+    if fundraiser_state.has_flag(FLAG_TOKEN_GATE) {
+        let gate_account = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        check_token_gate(
+            &fundraiser_state.gate_mint(),
+            fundraiser_state.gate_min_balance(),
+            contributor.key(),
+            gate_account,
+        )?;
+    }
 
-    // Access fundraiser account data to pick amount to raise value
-    let data = &mut fundraiser.try_borrow_mut_data()?;
-    let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+    // Once the maker blocked someone, contributors show their block record PDA is empty
+    if fundraiser_state.blocked_count() > 0 {
+        let block_record = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        check_not_blocked(fundraiser, contributor.key(), block_record)?;
+    }
 
-    let ix_data = load_ix_data::<ContributeIxData>(&instruction_data)?;
-    let amount = ix_data.amount();
+    // Regulated raises need an unexpired attestation of the contributor by the issuer
+    if fundraiser_state.has_flag(FLAG_ATTESTATION) {
+        let attestation = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        check_attestation(&fundraiser_state.issuer(), contributor.key(), attestation)?;
+    }
+
+    // Backers picking a perk take one of the limited slots of its tier
+    let tier = match ix_data.tier() {
+        Some(index) => {
+            if !fundraiser_state.has_flag(FLAG_TIERS) {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            let tiers = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let seed = &[TIERS_SEED, fundraiser.key().as_ref()];
+            let (pda_tiers, _) = find_program_address(seed, program_id);
+            if pda_tiers.ne(tiers.key()) {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            if !tiers.is_owned_by(&crate::ID) {
+                return Err(ProgramError::IllegalOwner);
+            }
+            if !tiers.is_writable() {
+                return Err(ProgramError::Immutable);
+            }
+            Some((tiers, index))
+        }
+        None => None,
+    };
+
+    // Sponsored fundraisers are passed the matching pool, which may not be funded yet.
+    // It is checked against its PDA first, so only the real pool being absent skips matching
+    let matching = if fundraiser_state.has_flag(FLAG_MATCHING) {
+        let matching_pool = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let pool_vault = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let seed: &[&[u8]] = &[MATCHING_SEED, fundraiser.key()];
+        let (pda_matching_pool, _) = find_program_address(seed, program_id);
+        if pda_matching_pool.ne(matching_pool.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Some((matching_pool, pool_vault))
+    } else {
+        None
+    };
+
+    // Fundraisers in a quadratic funding round report each contributor's new total to it
+    let round = if fundraiser_state.in_round() {
+        let round = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        Some((round, fundraiser_state.round()))
+    } else {
+        None
+    };
+
+    // Children of a campaign roll their progress up into it
+    let campaign = if fundraiser_state.has_flag(FLAG_CAMPAIGN) {
+        let campaign = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        Some((campaign, fundraiser_state.campaign()))
+    } else {
+        None
+    };
+
+    let allocations = match allocation {
+        Some(allocation_ix) => {
+            let allocations = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let seed = &[ALLOCATIONS_SEED, fundraiser.key().as_ref()];
+            let (pda_allocations, _) = find_program_address(seed, program_id);
+            if pda_allocations.ne(allocations.key()) {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            if !allocations.is_owned_by(&crate::ID) {
+                return Err(ProgramError::IllegalOwner);
+            }
+            if !allocations.is_writable() {
+                return Err(ProgramError::Immutable);
+            }
+            Some((allocations, allocation_ix))
+        }
+        None => None,
+    };
 
     pinocchio_log::log!("decimals: {}", decimals);
-    let am = amount / decimals as u64; //amount.checked_div(decimals as u64).unwrap();
-    pinocchio_log::log!("amount got: {}", am);
-    let min = 3_u64.pow(decimals as u32) as u64;
-    pinocchio_log::log!("minimum: {}", min);
     // Amount should be above minimum contribution
     // if am.lt(&(3_u8.pow(decimals as u32) as u64)) {
     //     msg!("Amount should be above minimum contribution");
@@ -212,15 +434,240 @@ pub fn process_contribute_instruction(
     // }
     // .invoke()?;
 
-    pinocchio_token::instructions::Transfer {
-        from: &contributor_ata,
-        to: &vault,
-        amount: 10,
-        authority: &contributor,
+    if pledge {
+        // nothing moves yet, the tokens stay with the contributor until Collect
+        msg!("Pledge recorded");
+    } else if native {
+        pinocchio_system::instructions::Transfer {
+            from: contributor,
+            to: vault,
+            lamports: amount,
+        }
+        .invoke()?;
+    } else if ix_data.wrap() {
+        // Top up the wSOL vault with lamports and let the token program account for them
+        pinocchio_system::instructions::Transfer {
+            from: contributor,
+            to: vault,
+            lamports: amount,
+        }
+        .invoke()?;
+
+        SyncNative {
+            native_token: vault,
+        }
+        .invoke()?;
+    } else {
+        pinocchio_token::instructions::Transfer {
+            from: &contributor_ata,
+            to: &vault,
+            amount: amount,
+            authority: &contributor,
+        }
+        .invoke()?;
     }
-    .invoke()?;
 
     msg!("Transfer successfull");
 
+    // The sponsor's match goes into the vault next to the contribution
+    let matched = match matching {
+        Some((matching_pool, pool_vault)) if !matching_pool.data_is_empty() => {
+            if !matching_pool.is_owned_by(&crate::ID) {
+                return Err(ProgramError::IllegalOwner);
+            }
+            let (p_bump, available) =
+                check_matching_pool(fundraiser, matching_pool, pool_vault, mint_to_raise.key())?;
+
+            let pool_state = {
+                let data = &mut matching_pool.try_borrow_mut_data()?;
+                *load_acc_data_mut_unchecked::<MatchingPoolData>(data)?
+            };
+            let already_matched = {
+                let data = &mut contributor_account.try_borrow_mut_data()?;
+                load_acc_data_mut_unchecked::<ContributorData>(data)?.matched()
+            };
+
+            let matched = pool_state.match_for(credited, already_matched, available)?;
+            if matched > 0 {
+                let p_seed = [p_bump];
+                let seed = [
+                    Seed::from(MATCHING_SEED),
+                    Seed::from(fundraiser.key()),
+                    Seed::from(&p_seed),
+                ];
+                pinocchio_token::instructions::Transfer {
+                    from: pool_vault,
+                    to: vault,
+                    amount: matched,
+                    authority: matching_pool,
+                }
+                .invoke_signed(&[Signer::from(&seed)])?;
+            }
+            matched
+        }
+        _ => 0,
+    };
+
+    // Record the contribution so claims and refunds can be computed from it
+    let added = credited
+        .checked_add(matched)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    fundraiser_state.set_current_amount(
+        fundraiser_state
+            .current_amount()
+            .checked_add(added)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    );
+    fundraiser_state.set_matched_amount(
+        fundraiser_state
+            .matched_amount()
+            .checked_add(matched)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    );
+
+    // Early contributions weigh more when the rewards are shared out
+    let shares = fundraiser_state.shares_for(credited, Clock::get()?.unix_timestamp as u64)?;
+    fundraiser_state.set_total_shares(
+        fundraiser_state
+            .total_shares()
+            .checked_add(shares)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    );
+
+    let receipt = fundraiser_state.has_flag(FLAG_RECEIPT);
+    let maker = fundraiser_state.maker();
+    let f_bump = [fundraiser_state.bump()];
+
+    // the fundraiser signs the receipt mint below, so its data must not be borrowed anymore
+    drop(data);
+
+    let contributed = {
+        let data = &mut contributor_account.try_borrow_mut_data()?;
+        let contributor_state = load_acc_data_mut_unchecked::<ContributorData>(data)?;
+        let contributed = contributor_state
+            .amount()
+            .checked_add(credited)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if cap > 0 && contributed > cap {
+            return Err(FundraiserError::ContributionCapExceeded.into());
+        }
+        contributor_state.set_amount(contributed);
+        contributor_state.set_shares(
+            contributor_state
+                .shares()
+                .checked_add(shares)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+        contributor_state.set_matched(
+            contributor_state
+                .matched()
+                .checked_add(matched)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+
+        if let Some((tiers, index)) = tier {
+            if contributor_state.tier().is_some() {
+                return Err(FundraiserError::TierAlreadySelected.into());
+            }
+            let data = &mut tiers.try_borrow_mut_data()?;
+            load_acc_data_mut_unchecked::<TiersData>(data)?.claim(index, contributed)?;
+            contributor_state.set_tier(Some(index));
+        }
+
+        // keep the raw amount per mint so refunds go back in the original currency
+        if let Some(index) = mint_index {
+            let deposit = &mut contributor_state.deposits[index];
+            deposit.set_raw(
+                deposit
+                    .raw()
+                    .checked_add(amount)
+                    .ok_or(ProgramError::ArithmeticOverflow)?,
+            );
+            deposit.set_credited(
+                deposit
+                    .credited()
+                    .checked_add(credited)
+                    .ok_or(ProgramError::ArithmeticOverflow)?,
+            );
+        }
+
+        // the whole pledge has to be approved to the fundraiser from a single account
+        if pledge {
+            let pledge_account = TokenAccount::from_account_info(contributor_ata)?;
+            if pledge_account.owner().ne(contributor.key())
+                || pledge_account.mint().ne(mint_to_raise.key())
+                || pledge_account.delegate() != Some(fundraiser.key())
+                || pledge_account.delegated_amount() < contributed
+            {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if contributed > credited
+                && contributor_state.pledge_account().ne(contributor_ata.key())
+            {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            contributor_state.set_pledge_account(contributor_ata.key());
+        }
+
+        // the sponsor's match follows the donor's allocation
+        if let Some((allocations, allocation_ix)) = allocations {
+            let data = &mut allocations.try_borrow_mut_data()?;
+            let allocations_state = load_acc_data_mut_unchecked::<AllocationsData>(data)?;
+            let amounts = allocation_ix.split(allocations_state.count(), added)?;
+            allocations_state.add(&amounts)?;
+
+            let mut allocated = contributor_state.allocated();
+            for (total, amount) in allocated.iter_mut().zip(amounts.iter()) {
+                *total = total
+                    .checked_add(*amount)
+                    .ok_or(ProgramError::ArithmeticOverflow)?;
+            }
+            contributor_state.set_allocated(&allocated);
+        }
+
+        contributed
+    };
+
+    if let Some((round, expected_round)) = round {
+        update_round_weight(
+            round,
+            &expected_round,
+            fundraiser.key(),
+            contributed - credited,
+            contributed,
+        )?;
+    }
+
+    if let Some((campaign, expected_campaign)) = campaign {
+        record_in_campaign(
+            campaign,
+            &expected_campaign,
+            added,
+            0,
+            contributed == credited,
+            false,
+        )?;
+    }
+
+    // Receipt tokens mirror the credited amount in the contributor's wallet
+    if receipt {
+        let receipt_mint = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let receipt_account = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        check_receipt_mint(fundraiser, receipt_mint)?;
+
+        let seed = [
+            Seed::from(FUNDRAISER_SEED),
+            Seed::from(&maker),
+            Seed::from(&f_bump),
+        ];
+        MintTo {
+            mint: receipt_mint,
+            account: receipt_account,
+            mint_authority: fundraiser,
+            amount: credited,
+        }
+        .invoke_signed(&[Signer::from(&seed)])?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,52 @@
+use crate::helpers::{check_signer, create_pda_account, load_acc_data_mut_unchecked};
+use crate::state::{CAMPAIGN_SEED, CampaignData};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::Seed;
+use pinocchio::program_error::ProgramError;
+use pinocchio::{ProgramResult, msg, pubkey};
+
+/// Creates the umbrella campaign of the signing owner. Children are linked to it when
+/// they are initialized with `FLAG_CAMPAIGN`.
+pub fn process_create_campaign_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        owner,    // mut signer
+        campaign, // mut init. seeds = [b"campaign", owner.key().as_ref()]
+        _system_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(owner)?;
+
+    let seeds: &[&[u8]] = &[CAMPAIGN_SEED, owner.key()];
+    let (campaign_pda, c_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if campaign_pda.ne(campaign.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !campaign.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let c_seed = [c_bump];
+    let seed = [
+        Seed::from(CAMPAIGN_SEED),
+        Seed::from(owner.key()),
+        Seed::from(&c_seed),
+    ];
+    create_pda_account::<CampaignData>(owner, campaign, &seed)?;
+
+    let data = &mut campaign.try_borrow_mut_data()?;
+    let campaign_state = load_acc_data_mut_unchecked::<CampaignData>(data)?;
+    campaign_state.set_owner(owner.key());
+    campaign_state.set_bump(c_bump);
+    campaign_state.add_padding();
+
+    msg!("Campaign created");
+
+    Ok(())
+}
@@ -1,7 +1,17 @@
-use crate::helpers::{DataLen, check_signer, load_acc_data_mut_unchecked, load_ix_data};
+use crate::helpers::{
+    DataLen, check_receipt_mint, check_signer, create_pda_account, load_acc_data_mut_unchecked,
+    load_ix_data, split_ix_data,
+};
 use crate::state::{
-    FUNDRAISER_SEED, FundraiserData, InitializeFundraiserIxData, MIN_AMOUNT_TO_RAISE,
-    SECONDS_PER_DAY,
+    ACCEPTED_MINTS_SEED, ALLOCATIONS_SEED, AcceptedMintIxData, AcceptedMintsData, AllocationsData,
+    AllowlistIxData, AttestationIxData, BPS_SCALER, BeneficiariesIxData, CAMPAIGN_SEED,
+    CampaignData, EarlyBirdIxData, FLAG_ALLOCATION, FLAG_ALLOWLIST, FLAG_ATTESTATION,
+    FLAG_CAMPAIGN, FLAG_EARLY_BIRD, FLAG_MATCHING, FLAG_MILESTONES, FLAG_MULTI_MINT, FLAG_NATIVE,
+    FLAG_ORACLE, FLAG_PLEDGE, FLAG_RECEIPT, FLAG_REWARD, FLAG_TIERS, FLAG_TOKEN_GATE, FLAG_VESTING,
+    FUNDRAISER_SEED, FundraiserData, FundraiserStatus, InitializeFundraiserIxData, MILESTONES_SEED,
+    MIN_AMOUNT_TO_RAISE, MilestonesData, MilestonesIxData, OracleIxData, PERCENTAGE_SCALER,
+    RECEIPT_SEED, SECONDS_PER_DAY, TIERS_SEED, TiersData, TiersIxData, TokenGateIxData,
+    USD_DECIMALS, VAULT_SEED, VestingIxData,
 };
 use pinocchio::account_info::AccountInfo;
 use pinocchio::instruction::Seed;
@@ -14,7 +24,7 @@ use pinocchio::{ProgramResult, msg, pubkey};
 //use pinocchio_associated_token_account::solana_program;
 use pinocchio_associated_token_account::instructions::Create as Create_ATA;
 use pinocchio_system::instructions::{CreateAccount, CreateAccountWithSeed};
-use pinocchio_token::instructions::InitializeAccount;
+use pinocchio_token::instructions::{InitializeAccount, InitializeMint2};
 use pinocchio_token::state::{Mint, TokenAccount};
 //use spl_token::solana_program::program_pack::Pack;
 
@@ -31,6 +41,10 @@ pub fn process_initialize_instruction(
         token_program,
         associated_token_program,
         rent_sysvar,
+        // in order: milestones PDA (FLAG_MILESTONES), accepted mints PDA (FLAG_MULTI_MINT),
+        // receipt mint PDA (FLAG_RECEIPT), reward mint and reward vault (FLAG_REWARD),
+        // tiers PDA (FLAG_TIERS), campaign PDA and its owner as signer (FLAG_CAMPAIGN),
+        // allocations PDA (FLAG_ALLOCATION)
         remaining @ ..,
     ] = accounts
     else {
@@ -55,28 +69,89 @@ pub fn process_initialize_instruction(
         return Err(ProgramError::AccountAlreadyInitialized);
     }
 
-    let ix_data = load_ix_data::<InitializeFundraiserIxData>(&instruction_data)?;
+    let (ix_data, extra_data) = split_ix_data::<InitializeFundraiserIxData>(&instruction_data)?;
 
     if ix_data.duration() == 0 {
         return Err(ProgramError::InvalidInstructionData);
     }
 
-    //pinocchio_log::log!("Initialize instruction data {}", ix_data.duration());
+    if ix_data.cancel_threshold() as u64 > PERCENTAGE_SCALER {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // Funds are released either in tranches or along a vesting schedule, not both
+    if ix_data.has_flag(FLAG_MILESTONES) && ix_data.has_flag(FLAG_VESTING) {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // Multi-mint and oracle priced fundraisers count the raise in a normalized unit and
+    // pay out every vault in a single claim, so they release neither in tranches nor by
+    // vesting. Multi-mint fundraisers only hold SPL tokens at fixed rates.
+    if (ix_data.has_flag(FLAG_MULTI_MINT) || ix_data.has_flag(FLAG_ORACLE))
+        && (ix_data.has_flag(FLAG_MILESTONES) || ix_data.has_flag(FLAG_VESTING))
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if ix_data.has_flag(FLAG_MULTI_MINT)
+        && (ix_data.has_flag(FLAG_NATIVE) || ix_data.has_flag(FLAG_ORACLE))
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
 
-    let mut decimals: u8 = 0;
+    // Matches are paid in the raised mint and counted 1:1 towards the target
+    if ix_data.has_flag(FLAG_MATCHING)
+        && (ix_data.has_flag(FLAG_NATIVE)
+            || ix_data.has_flag(FLAG_MULTI_MINT)
+            || ix_data.has_flag(FLAG_ORACLE))
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
 
-    // Access mint account to retrieve decimals
-    // Try to parse as TokenAccount
-    let m = Mint::from_account_info(mint_to_raise).unwrap();
-    match Mint::from_account_info(mint_to_raise) {
-        Ok(m_account) => {
-            if !m_account.is_initialized() {
-                return Err(pinocchio::program_error::UNINITIALIZED_ACCOUNT.into());
+    // Donor-directed raises pay every beneficiary out of a single token vault at once
+    if ix_data.has_flag(FLAG_ALLOCATION)
+        && (ix_data.has_flag(FLAG_MILESTONES)
+            || ix_data.has_flag(FLAG_VESTING)
+            || ix_data.has_flag(FLAG_NATIVE)
+            || ix_data.has_flag(FLAG_MULTI_MINT)
+            || ix_data.has_flag(FLAG_ORACLE))
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // Pledges are pulled from token accounts of the raised mint in one go, and nothing
+    // is paid in before the deadline to be matched, allocated or rolled up meanwhile
+    if ix_data.has_flag(FLAG_PLEDGE)
+        && (ix_data.has_flag(FLAG_MILESTONES)
+            || ix_data.has_flag(FLAG_VESTING)
+            || ix_data.has_flag(FLAG_NATIVE)
+            || ix_data.has_flag(FLAG_MULTI_MINT)
+            || ix_data.has_flag(FLAG_ORACLE)
+            || ix_data.has_flag(FLAG_MATCHING)
+            || ix_data.has_flag(FLAG_CAMPAIGN)
+            || ix_data.has_flag(FLAG_ALLOCATION))
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    //pinocchio_log::log!("Initialize instruction data {}", ix_data.duration());
+
+    let native = ix_data.has_flag(FLAG_NATIVE);
+    let mut decimals: u8 = 9;
+
+    // Native SOL fundraisers have no mint, the mint account slot is ignored
+    if !native {
+        // Access mint account to retrieve decimals
+        // Try to parse as TokenAccount
+        match Mint::from_account_info(mint_to_raise) {
+            Ok(m_account) => {
+                if !m_account.is_initialized() {
+                    return Err(pinocchio::program_error::UNINITIALIZED_ACCOUNT.into());
+                }
+                decimals = m_account.decimals();
+            }
+            Err(e) => {
+                return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
             }
-            decimals = m_account.decimals();
-        }
-        Err(e) => {
-            return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
         }
     }
 
@@ -147,12 +222,21 @@ pub fn process_initialize_instruction(
         let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
 
         fundraiser_state.set_maker(maker.key());
-        fundraiser_state.set_mint_to_raise(mint_to_raise.key());
+        if native {
+            fundraiser_state.set_mint_to_raise(&Pubkey::default());
+        } else {
+            fundraiser_state.set_mint_to_raise(mint_to_raise.key());
+        }
         fundraiser_state.set_amount_to_raise(ix_data.amount_to_raise());
         fundraiser_state.set_current_amount(0u64);
+        fundraiser_state.set_matched_amount(0u64);
         fundraiser_state.set_time_started(unix_timestamp as u64);
         fundraiser_state.set_duration(ix_data.duration());
         fundraiser_state.set_bump(f_bump);
+        fundraiser_state.set_released_amount(0u64);
+        fundraiser_state.set_status(FundraiserStatus::Active);
+        fundraiser_state.set_flags(ix_data.flags());
+        fundraiser_state.set_cancel_threshold(ix_data.cancel_threshold());
         fundraiser_state.add_padding();
 
         msg!("PDA account created");
@@ -161,15 +245,374 @@ pub fn process_initialize_instruction(
         // drop(mint_data);
     }
 
-    Create_ATA {
-        funding_account: maker,
-        account: vault,
-        wallet: fundraiser,
-        mint: mint_to_raise,
-        system_program: system_program,
-        token_program: token_program,
+    if native {
+        let (pda_vault, v_bump) =
+            pubkey::find_program_address(&[VAULT_SEED, fundraiser.key().as_ref()], &crate::ID);
+        if pda_vault.ne(vault.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // The vault holds no data, only the lamports contributed on top of its rent
+        let v_seed = [v_bump];
+        let seed = [
+            Seed::from(VAULT_SEED),
+            Seed::from(fundraiser.key()),
+            Seed::from(&v_seed),
+        ];
+        CreateAccount {
+            from: maker,
+            to: vault,
+            lamports: Rent::get()?.minimum_balance(0),
+            space: 0,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&[Signer::from(&seed)])?;
+    } else {
+        Create_ATA {
+            funding_account: maker,
+            account: vault,
+            wallet: fundraiser,
+            mint: mint_to_raise,
+            system_program: system_program,
+            token_program: token_program,
+        }
+        .invoke_signed(&[signer_seeds])?;
+    }
+
+    let mut remaining = remaining.iter();
+    let mut extra_data = extra_data;
+
+    if ix_data.has_flag(FLAG_MILESTONES) {
+        let milestones = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let (milestones_ix, rest) = split_ix_data::<MilestonesIxData>(extra_data)?;
+        extra_data = rest;
+
+        milestones_ix.validate()?;
+
+        let (pda_milestones, m_bump) =
+            pubkey::find_program_address(&[MILESTONES_SEED, fundraiser.key().as_ref()], &crate::ID);
+        if pda_milestones.ne(milestones.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !milestones.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        let m_seed = [m_bump];
+        let seed = [
+            Seed::from(MILESTONES_SEED),
+            Seed::from(fundraiser.key()),
+            Seed::from(&m_seed),
+        ];
+        create_pda_account::<MilestonesData>(maker, milestones, &seed)?;
+
+        let data = &mut milestones.try_borrow_mut_data()?;
+        let milestones_state = load_acc_data_mut_unchecked::<MilestonesData>(data)?;
+        milestones_state.set_count(milestones_ix.count());
+        milestones_state.set_next_tranche(0);
+        milestones_state.set_bump(m_bump);
+        milestones_state.set_quorum(milestones_ix.quorum());
+        milestones_state.set_threshold(milestones_ix.threshold());
+        milestones_state.set_voting_period(milestones_ix.voting_period());
+        milestones_state.tranches = milestones_ix.tranches;
+        for tranche in milestones_state.tranches.iter_mut() {
+            tranche.set_votes_for(0);
+            tranche.set_votes_against(0);
+            tranche.set_attempt(0);
+            tranche.add_padding();
+        }
+        milestones_state.add_padding();
+
+        msg!("milestones PDA created");
+    }
+
+    if ix_data.has_flag(FLAG_VESTING) {
+        let (vesting_ix, rest) = split_ix_data::<VestingIxData>(extra_data)?;
+        extra_data = rest;
+
+        if vesting_ix.duration() == 0 || vesting_ix.cliff() > vesting_ix.duration() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        fundraiser_state.set_vesting_cliff(vesting_ix.cliff());
+        fundraiser_state.set_vesting_duration(vesting_ix.duration());
+    }
+
+    if ix_data.has_flag(FLAG_MULTI_MINT) {
+        let accepted_mints = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let (mint_ix, rest) = split_ix_data::<AcceptedMintIxData>(extra_data)?;
+        extra_data = rest;
+
+        let (pda_accepted_mints, a_bump) = pubkey::find_program_address(
+            &[ACCEPTED_MINTS_SEED, fundraiser.key().as_ref()],
+            &crate::ID,
+        );
+        if pda_accepted_mints.ne(accepted_mints.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !accepted_mints.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        let a_seed = [a_bump];
+        let seed = [
+            Seed::from(ACCEPTED_MINTS_SEED),
+            Seed::from(fundraiser.key()),
+            Seed::from(&a_seed),
+        ];
+        create_pda_account::<AcceptedMintsData>(maker, accepted_mints, &seed)?;
+
+        // the fundraiser's own mint is always the first accepted mint
+        let data = &mut accepted_mints.try_borrow_mut_data()?;
+        let accepted_state = load_acc_data_mut_unchecked::<AcceptedMintsData>(data)?;
+        accepted_state.set_count(0);
+        accepted_state.set_bump(a_bump);
+        accepted_state.add_padding();
+        mint_ix.register(accepted_state, mint_to_raise.key())?;
+
+        msg!("accepted mints PDA created");
+    }
+
+    if ix_data.has_flag(FLAG_ORACLE) {
+        let (oracle_ix, rest) = split_ix_data::<OracleIxData>(extra_data)?;
+        extra_data = rest;
+
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        fundraiser_state.set_price_account(&oracle_ix.price_account());
+    }
+
+    if ix_data.has_flag(FLAG_ALLOWLIST) {
+        let (allowlist_ix, rest) = split_ix_data::<AllowlistIxData>(extra_data)?;
+        extra_data = rest;
+
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        fundraiser_state.set_merkle_root(&allowlist_ix.merkle_root);
+    }
+
+    if ix_data.has_flag(FLAG_TOKEN_GATE) {
+        let (gate_ix, rest) = split_ix_data::<TokenGateIxData>(extra_data)?;
+        extra_data = rest;
+
+        if gate_ix.min_balance() == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        fundraiser_state.set_gate_mint(&gate_ix.gate_mint());
+        fundraiser_state.set_gate_min_balance(gate_ix.min_balance());
+    }
+
+    if ix_data.has_flag(FLAG_ATTESTATION) {
+        let (attestation_ix, rest) = split_ix_data::<AttestationIxData>(extra_data)?;
+        extra_data = rest;
+
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        fundraiser_state.set_issuer(&attestation_ix.issuer());
+    }
+
+    if ix_data.has_flag(FLAG_RECEIPT) {
+        let receipt_mint = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let r_bump = check_receipt_mint(fundraiser, receipt_mint)?;
+        if !receipt_mint.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        let r_seed = [r_bump];
+        let seed = [
+            Seed::from(RECEIPT_SEED),
+            Seed::from(fundraiser.key()),
+            Seed::from(&r_seed),
+        ];
+        CreateAccount {
+            from: maker,
+            to: receipt_mint,
+            lamports: Rent::get()?.minimum_balance(Mint::LEN),
+            space: Mint::LEN as u64,
+            owner: token_program.key(),
+        }
+        .invoke_signed(&[Signer::from(&seed)])?;
+
+        // receipts are denominated like the credited amounts, USD for oracle priced raises
+        let receipt_decimals = if ix_data.has_flag(FLAG_ORACLE) {
+            USD_DECIMALS
+        } else {
+            decimals
+        };
+        InitializeMint2 {
+            mint: receipt_mint,
+            decimals: receipt_decimals,
+            mint_authority: fundraiser.key(),
+            freeze_authority: None,
+        }
+        .invoke()?;
+
+        msg!("receipt mint created");
+    }
+
+    if ix_data.has_flag(FLAG_REWARD) {
+        let reward_mint = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let reward_vault = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        if !Mint::from_account_info(reward_mint)?.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        {
+            let data = &mut fundraiser.try_borrow_mut_data()?;
+            let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+            fundraiser_state.set_reward_mint(reward_mint.key());
+        }
+
+        // the maker funds the reward vault with plain token transfers
+        Create_ATA {
+            funding_account: maker,
+            account: reward_vault,
+            wallet: fundraiser,
+            mint: reward_mint,
+            system_program: system_program,
+            token_program: token_program,
+        }
+        .invoke()?;
+
+        msg!("reward vault created");
+    }
+
+    if ix_data.has_flag(FLAG_TIERS) {
+        let tiers = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let (tiers_ix, rest) = split_ix_data::<TiersIxData>(extra_data)?;
+        extra_data = rest;
+
+        tiers_ix.validate()?;
+
+        let (pda_tiers, t_bump) =
+            pubkey::find_program_address(&[TIERS_SEED, fundraiser.key().as_ref()], &crate::ID);
+        if pda_tiers.ne(tiers.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !tiers.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        let t_seed = [t_bump];
+        let seed = [
+            Seed::from(TIERS_SEED),
+            Seed::from(fundraiser.key()),
+            Seed::from(&t_seed),
+        ];
+        create_pda_account::<TiersData>(maker, tiers, &seed)?;
+
+        let data = &mut tiers.try_borrow_mut_data()?;
+        let tiers_state = load_acc_data_mut_unchecked::<TiersData>(data)?;
+        tiers_state.set_count(tiers_ix.count());
+        tiers_state.set_bump(t_bump);
+        tiers_state.tiers = tiers_ix.tiers;
+        for tier in tiers_state.tiers.iter_mut() {
+            tier.set_claimed(0);
+        }
+        tiers_state.add_padding();
+
+        msg!("tiers PDA created");
+    }
+
+    if ix_data.has_flag(FLAG_EARLY_BIRD) {
+        let (early_bird_ix, rest) = split_ix_data::<EarlyBirdIxData>(extra_data)?;
+        extra_data = rest;
+
+        if early_bird_ix.bonus_period() == 0 || early_bird_ix.bonus_bps() as u64 > BPS_SCALER {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        fundraiser_state.set_bonus_period(early_bird_ix.bonus_period());
+        fundraiser_state.set_bonus_bps(early_bird_ix.bonus_bps());
+    }
+
+    // Children join their campaign with the approval of its owner
+    if ix_data.has_flag(FLAG_CAMPAIGN) {
+        let campaign = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let campaign_owner = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        check_signer(campaign_owner)?;
+
+        let seeds: &[&[u8]] = &[CAMPAIGN_SEED, campaign_owner.key()];
+        let (campaign_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+        if campaign_pda.ne(campaign.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !campaign.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IllegalOwner);
+        }
+        if !campaign.is_writable() {
+            return Err(ProgramError::Immutable);
+        }
+
+        {
+            let data = &mut campaign.try_borrow_mut_data()?;
+            let campaign_state = load_acc_data_mut_unchecked::<CampaignData>(data)?;
+            if campaign_state.owner().ne(campaign_owner.key()) {
+                return Err(ProgramError::IncorrectAuthority);
+            }
+            campaign_state.add_child(ix_data.amount_to_raise())?;
+        }
+
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        load_acc_data_mut_unchecked::<FundraiserData>(data)?.set_campaign(campaign.key());
+
+        msg!("fundraiser linked to its campaign");
+    }
+
+    if ix_data.has_flag(FLAG_ALLOCATION) {
+        let allocations = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let (beneficiaries_ix, rest) = split_ix_data::<BeneficiariesIxData>(extra_data)?;
+        extra_data = rest;
+
+        beneficiaries_ix.validate()?;
+
+        let (pda_allocations, a_bump) = pubkey::find_program_address(
+            &[ALLOCATIONS_SEED, fundraiser.key().as_ref()],
+            &crate::ID,
+        );
+        if pda_allocations.ne(allocations.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !allocations.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        let a_seed = [a_bump];
+        let seed = [
+            Seed::from(ALLOCATIONS_SEED),
+            Seed::from(fundraiser.key()),
+            Seed::from(&a_seed),
+        ];
+        create_pda_account::<AllocationsData>(maker, allocations, &seed)?;
+
+        let data = &mut allocations.try_borrow_mut_data()?;
+        let allocations_state = load_acc_data_mut_unchecked::<AllocationsData>(data)?;
+        allocations_state.set_count(beneficiaries_ix.count());
+        allocations_state.set_bump(a_bump);
+        for (beneficiary, wallet) in allocations_state
+            .beneficiaries
+            .iter_mut()
+            .zip(beneficiaries_ix.wallets.iter())
+        {
+            beneficiary.wallet = *wallet;
+        }
+        allocations_state.add_padding();
+
+        msg!("allocations PDA created");
+    }
+
+    // every optional section announced by the flags must have been consumed
+    if !extra_data.is_empty() {
+        return Err(ProgramError::InvalidInstructionData);
     }
-    .invoke_signed(&[signer_seeds])?;
 
     // Creating the instruction instance
     // let initialize_account_instruction = InitializeAccount {
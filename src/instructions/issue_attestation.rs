@@ -0,0 +1,59 @@
+use crate::helpers::{check_signer, create_pda_account, load_acc_data_mut_unchecked, load_ix_data};
+use crate::state::{ATTESTATION_SEED, AttestationData, IssueAttestationIxData};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::Seed;
+use pinocchio::program_error::ProgramError;
+use pinocchio::{ProgramResult, msg, pubkey};
+
+/// Attests a contributor on behalf of the signing issuer. Issuing again for the same
+/// contributor replaces the expiry, so an issuer can renew or revoke its attestations.
+pub fn process_issue_attestation_instruction(
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        issuer,      // mut signer
+        contributor, //
+        attestation, // mut init_if_needed. seeds = [b"attestation", issuer.key().as_ref(), contributor.key().as_ref()]
+        _system_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(issuer)?;
+
+    let ix_data = load_ix_data::<IssueAttestationIxData>(instruction_data)?;
+
+    let seeds: &[&[u8]] = &[ATTESTATION_SEED, issuer.key(), contributor.key()];
+    let (attestation_pda, a_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if attestation_pda.ne(attestation.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if attestation.data_is_empty() {
+        let a_seed = [a_bump];
+        let seed = [
+            Seed::from(ATTESTATION_SEED),
+            Seed::from(issuer.key()),
+            Seed::from(contributor.key()),
+            Seed::from(&a_seed),
+        ];
+        create_pda_account::<AttestationData>(issuer, attestation, &seed)?;
+    } else if !attestation.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let data = &mut attestation.try_borrow_mut_data()?;
+    let attestation_state = load_acc_data_mut_unchecked::<AttestationData>(data)?;
+    attestation_state.set_issuer(issuer.key());
+    attestation_state.set_contributor(contributor.key());
+    attestation_state.set_expires_at(ix_data.expires_at());
+    attestation_state.set_bump(a_bump);
+    attestation_state.add_padding();
+
+    msg!("Attestation issued");
+
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{
+    check_matching_pool, check_signer, create_pda_account, load_acc_data_mut_unchecked,
+    load_ix_data,
+};
+use crate::state::{
+    FLAG_MATCHING, FundMatchingPoolIxData, FundraiserData, FundraiserStatus, MATCHING_SEED,
+    MatchingPoolData,
+};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::{Seed, Signer};
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+use pinocchio::{ProgramResult, msg, pubkey};
+use pinocchio_associated_token_account::instructions::Create as Create_ATA;
+use pinocchio_token::instructions::Transfer;
+use pinocchio_token::state::TokenAccount;
+
+/// Creates the matching pool of a fundraiser on first use, then tops it up with the
+/// sponsor's tokens. The ratio and donor cap are set when the pool is created, which the
+/// maker co-signs so nobody else can claim the pool and its terms.
+pub fn process_fund_matching_pool_instruction(
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        sponsor,       // mut signer
+        maker,         // signer when the pool is created
+        fundraiser,    //
+        mint_to_raise, // mint
+        matching_pool, // mut init_if_needed. seeds = [b"matching", fundraiser.key().as_ref()]
+        pool_vault,    // mut ata init_if_needed. authority = matching_pool
+        sponsor_ata,   // mut
+        system_program,
+        token_program,
+        _associated_token_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(sponsor)?;
+
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let ix_data = load_ix_data::<FundMatchingPoolIxData>(instruction_data)?;
+    if ix_data.amount() == 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let fundraiser_maker = {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+
+        if !fundraiser_state.has_flag(FLAG_MATCHING) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if fundraiser_state.status()? != FundraiserStatus::Active
+            || Clock::get()?.unix_timestamp as u64 >= fundraiser_state.deadline()
+        {
+            return Err(FundraiserError::FundraiserNotActive.into());
+        }
+        if fundraiser_state.mint_to_raise().ne(mint_to_raise.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        fundraiser_state.maker()
+    };
+
+    let seeds: &[&[u8]] = &[MATCHING_SEED, fundraiser.key()];
+    let (pool_pda, p_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if pool_pda.ne(matching_pool.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if matching_pool.data_is_empty() {
+        if ix_data.ratio_bps() == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        check_signer(maker)?;
+        if fundraiser_maker.ne(maker.key()) {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+
+        let p_seed = [p_bump];
+        let seed = [
+            Seed::from(MATCHING_SEED),
+            Seed::from(fundraiser.key()),
+            Seed::from(&p_seed),
+        ];
+        create_pda_account::<MatchingPoolData>(sponsor, matching_pool, &seed)?;
+
+        {
+            let data = &mut matching_pool.try_borrow_mut_data()?;
+            let pool_state = load_acc_data_mut_unchecked::<MatchingPoolData>(data)?;
+            pool_state.set_sponsor(sponsor.key());
+            pool_state.set_donor_cap(ix_data.donor_cap());
+            pool_state.set_ratio_bps(ix_data.ratio_bps());
+            pool_state.set_bump(p_bump);
+            pool_state.add_padding();
+        }
+
+        Create_ATA {
+            funding_account: sponsor,
+            account: pool_vault,
+            wallet: matching_pool,
+            mint: mint_to_raise,
+            system_program: system_program,
+            token_program: token_program,
+        }
+        .invoke()?;
+
+        msg!("matching pool created");
+    } else {
+        if !matching_pool.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        // only the sponsor gets the pool back, so nobody else may add to it
+        let data = &mut matching_pool.try_borrow_mut_data()?;
+        let pool_state = load_acc_data_mut_unchecked::<MatchingPoolData>(data)?;
+        if pool_state.sponsor().ne(sponsor.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    check_matching_pool(fundraiser, matching_pool, pool_vault, mint_to_raise.key())?;
+
+    Transfer {
+        from: sponsor_ata,
+        to: pool_vault,
+        authority: sponsor,
+        amount: ix_data.amount(),
+    }
+    .invoke()?;
+
+    msg!("Matching pool funded");
+
+    Ok(())
+}
+
+/// Returns what is left in the matching pool to its sponsor once the fundraiser no
+/// longer takes contributions: after the deadline, or when it was cancelled, succeeded
+/// or closed. Matches given back by refunds can be withdrawn again later.
+pub fn process_withdraw_matching_pool_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        sponsor,       // signer
+        fundraiser,    //
+        matching_pool, // seeds = [b"matching", fundraiser.key().as_ref()]
+        pool_vault,    // mut ata. authority = matching_pool
+        sponsor_ata,   // mut
+        _token_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(sponsor)?;
+
+    if matching_pool.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !matching_pool.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let pool_state = {
+        let data = &mut matching_pool.try_borrow_mut_data()?;
+        *load_acc_data_mut_unchecked::<MatchingPoolData>(data)?
+    };
+    if pool_state.sponsor().ne(sponsor.key()) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // a closed fundraiser has paid out and takes no contributions anymore
+    if !fundraiser.data_is_empty() {
+        if !fundraiser.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        if fundraiser_state.status()? == FundraiserStatus::Active
+            && (Clock::get()?.unix_timestamp as u64) < fundraiser_state.deadline()
+        {
+            return Err(FundraiserError::MatchingPoolLocked.into());
+        }
+    }
+
+    let mint = *TokenAccount::from_account_info(pool_vault)?.mint();
+    let (p_bump, available) = check_matching_pool(fundraiser, matching_pool, pool_vault, &mint)?;
+    if available == 0 {
+        return Err(FundraiserError::NothingToClaim.into());
+    }
+
+    let p_seed = [p_bump];
+    let seed = [
+        Seed::from(MATCHING_SEED),
+        Seed::from(fundraiser.key()),
+        Seed::from(&p_seed),
+    ];
+    Transfer {
+        from: pool_vault,
+        to: sponsor_ata,
+        authority: matching_pool,
+        amount: available,
+    }
+    .invoke_signed(&[Signer::from(&seed)])?;
+
+    msg!("Matching pool withdrawn");
+
+    Ok(())
+}
@@ -1,18 +1,73 @@
+mod block;
+mod cancel;
+mod cancel_vote;
+mod cast_vote;
 mod check_contribution;
+mod claim_reward;
+mod claim_tranche;
+mod collect;
 mod contribute;
+mod create_campaign;
 mod initialize;
+mod issue_attestation;
+mod matching_pool;
 mod refund;
+mod register_mint;
+mod relay_contribution;
+mod round;
+mod subscription;
+mod transfer_position;
 
+pub use block::*;
+pub use cancel::*;
+pub use cancel_vote::*;
+pub use cast_vote::*;
 pub use check_contribution::*;
+pub use claim_reward::*;
+pub use claim_tranche::*;
+pub use collect::*;
 pub use contribute::*;
+pub use create_campaign::*;
 pub use initialize::*;
+pub use issue_attestation::*;
+pub use matching_pool::*;
 pub use refund::*;
+pub use register_mint::*;
+pub use relay_contribution::*;
+pub use round::*;
+pub use subscription::*;
+pub use transfer_position::*;
 
 pub enum FundraiserInstructions {
     Initialize = 0,
     Contribute = 1,
     Refund = 2,
     Check = 3,
+    ClaimTranche = 4,
+    Cancel = 5,
+    CastVote = 6,
+    ProposeCancel = 7,
+    VoteCancel = 8,
+    RegisterMint = 9,
+    IssueAttestation = 10,
+    Block = 11,
+    Unblock = 12,
+    TransferPosition = 13,
+    ClaimReward = 14,
+    FundMatchingPool = 15,
+    WithdrawMatchingPool = 16,
+    CreateRound = 17,
+    JoinRound = 18,
+    FinalizeRound = 19,
+    DistributeRound = 20,
+    CreateCampaign = 21,
+    Collect = 22,
+    CreateSubscription = 23,
+    ProcessSubscription = 24,
+    CancelSubscription = 25,
+    RelayContribution = 26,
+    CloseRewards = 27,
+    ReopenVote = 28,
 }
 
 impl TryFrom<&u8> for FundraiserInstructions {
@@ -24,7 +79,32 @@ impl TryFrom<&u8> for FundraiserInstructions {
             0 => Ok(FundraiserInstructions::Initialize),
             1 => Ok(FundraiserInstructions::Contribute),
             2 => Ok(FundraiserInstructions::Refund),
-            // 3 => Ok(FundraiserInstrctions::MakeV2),
+            3 => Ok(FundraiserInstructions::Check),
+            4 => Ok(FundraiserInstructions::ClaimTranche),
+            5 => Ok(FundraiserInstructions::Cancel),
+            6 => Ok(FundraiserInstructions::CastVote),
+            7 => Ok(FundraiserInstructions::ProposeCancel),
+            8 => Ok(FundraiserInstructions::VoteCancel),
+            9 => Ok(FundraiserInstructions::RegisterMint),
+            10 => Ok(FundraiserInstructions::IssueAttestation),
+            11 => Ok(FundraiserInstructions::Block),
+            12 => Ok(FundraiserInstructions::Unblock),
+            13 => Ok(FundraiserInstructions::TransferPosition),
+            14 => Ok(FundraiserInstructions::ClaimReward),
+            15 => Ok(FundraiserInstructions::FundMatchingPool),
+            16 => Ok(FundraiserInstructions::WithdrawMatchingPool),
+            17 => Ok(FundraiserInstructions::CreateRound),
+            18 => Ok(FundraiserInstructions::JoinRound),
+            19 => Ok(FundraiserInstructions::FinalizeRound),
+            20 => Ok(FundraiserInstructions::DistributeRound),
+            21 => Ok(FundraiserInstructions::CreateCampaign),
+            22 => Ok(FundraiserInstructions::Collect),
+            23 => Ok(FundraiserInstructions::CreateSubscription),
+            24 => Ok(FundraiserInstructions::ProcessSubscription),
+            25 => Ok(FundraiserInstructions::CancelSubscription),
+            26 => Ok(FundraiserInstructions::RelayContribution),
+            27 => Ok(FundraiserInstructions::CloseRewards),
+            28 => Ok(FundraiserInstructions::ReopenVote),
             _ => Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
         }
     }
@@ -1,7 +1,14 @@
-use crate::helpers::{DataLen, check_signer, load_acc_data_mut_unchecked, load_ix_data};
+use crate::helpers::{
+    DataLen, check_matching_pool, check_native_vault, check_receipt_mint, check_signer,
+    check_token_vault, load_acc_data_mut_unchecked, load_ix_data, pro_rata_share,
+    record_in_campaign, transfer_from_vault, unwrap_sol, update_round_weight, vault_balance,
+    wants_unwrap, withdraw_cancel_vote,
+};
 use crate::state::{
-    FUNDRAISER_SEED, FundraiserData, InitializeFundraiserIxData, MIN_AMOUNT_TO_RAISE,
-    SECONDS_PER_DAY,
+    ACCEPTED_MINTS_SEED, ALLOCATIONS_SEED, AcceptedMintsData, AllocationsData, FLAG_ALLOCATION,
+    FLAG_CAMPAIGN, FLAG_MULTI_MINT, FLAG_NATIVE, FLAG_PLEDGE, FLAG_RECEIPT, FUNDRAISER_SEED,
+    FundraiserData, FundraiserStatus, InitializeFundraiserIxData, MAX_BENEFICIARIES,
+    MIN_AMOUNT_TO_RAISE, MintDeposit, SECONDS_PER_DAY, TIERS_SEED, TiersData,
 };
 use pinocchio::account_info::AccountInfo;
 use pinocchio::instruction::Seed;
@@ -14,9 +21,10 @@ use pinocchio::{ProgramResult, msg, pubkey};
 //use pinocchio_associated_token_account::solana_program;
 use pinocchio_associated_token_account::instructions::Create as Create_ATA;
 use pinocchio_system::instructions::{CreateAccount, CreateAccountWithSeed};
-use pinocchio_token::instructions::InitializeAccount;
-use pinocchio_token::state::{Mint, TokenAccount};
+use pinocchio_token::instructions::{Burn, InitializeAccount};
+use pinocchio_token::state::Mint;
 //use spl_token::solana_program::program_pack::Pack;
+use crate::errors::FundraiserError;
 use crate::state::ContributorData;
 
 pub fn process_refund_instruction(
@@ -35,15 +43,18 @@ pub fn process_refund_instruction(
         system_program,
         associated_token_program,
         rent_sysvar,
+        // in order: accepted mints PDA (FLAG_MULTI_MINT), tiers PDA (when the contributor
+        // holds a tier), matching pool PDA and its vault (when the contributor was matched),
+        // round PDA (once the fundraiser joined a round), campaign PDA (FLAG_CAMPAIGN),
+        // allocations PDA (FLAG_ALLOCATION), receipt mint PDA and the contributor's receipt
+        // token account (FLAG_RECEIPT), cancel proposal PDA and the contributor's cancel vote
+        // record (when the contributor voted to cancel a fundraiser that is still active)
         remaining @ ..,
     ] = accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    // Check if the fundraising duration has been reached
-    // Check if the fundraiser target has been met
-    //
     check_signer(contributor)?;
 
     // validate fundraiser PDA
@@ -66,12 +77,54 @@ pub fn process_refund_instruction(
         return Err(ProgramError::IllegalOwner);
     }
 
-    {
+    let unwrap = wants_unwrap(instruction_data)?;
+    let now = Clock::get()?.unix_timestamp as u64;
+
+    let (
+        current_amount,
+        cancelled,
+        native,
+        multi_mint,
+        receipt,
+        mut mint,
+        round,
+        campaign,
+        allocation,
+    ) = {
         let data = &mut fundraiser.try_borrow_mut_data()?;
         let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
-    }
+        // pledgers just revoke their approval
+        if fundraiser_state.has_flag(FLAG_PLEDGE) {
+            return Err(FundraiserError::PledgeNotRefundable.into());
+        }
+        // money that went into a successful raise stays there, it is what the maker's
+        // tranches, vesting and the rewards are computed from
+        if !fundraiser_state.is_refundable(now)? {
+            return Err(FundraiserError::NotRefundable.into());
+        }
+        (
+            fundraiser_state.current_amount(),
+            fundraiser_state.status()? == FundraiserStatus::Cancelled,
+            fundraiser_state.has_flag(FLAG_NATIVE),
+            fundraiser_state.has_flag(FLAG_MULTI_MINT),
+            fundraiser_state.has_flag(FLAG_RECEIPT),
+            fundraiser_state.mint_to_raise(),
+            fundraiser_state
+                .in_round()
+                .then(|| fundraiser_state.round()),
+            fundraiser_state
+                .has_flag(FLAG_CAMPAIGN)
+                .then(|| fundraiser_state.campaign()),
+            fundraiser_state.has_flag(FLAG_ALLOCATION),
+        )
+    };
 
     // validate contributor_account PDA
+    let seeds: &[&[u8]] = &[b"contributor", fundraiser.key(), contributor.key()];
+    let (contributor_account_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if contributor_account_pda.ne(contributor_account.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
     if contributor_account.data_is_empty() {
         return Err(ProgramError::UninitializedAccount);
     }
@@ -83,22 +136,151 @@ pub fn process_refund_instruction(
         return Err(ProgramError::IllegalOwner);
     }
 
-    let mut amount_to_refund: u64 = 0;
+    let mut contributed: u64 = 0;
+    let tier;
+    let matched;
+    let voted_to_cancel;
     {
         let data = &mut contributor_account.try_borrow_mut_data()?;
         let contributor_account_state = load_acc_data_mut_unchecked::<ContributorData>(data)?;
-        amount_to_refund = contributor_account_state.amount();
+        // the rewards of a contribution are only paid for money that stays in the raise
+        if contributor_account_state.reward_claimed() {
+            return Err(FundraiserError::RewardAlreadyClaimed.into());
+        }
+        // the weight of a position voted with must not be recycled while the vote is open,
+        // votes stop mattering once the fundraiser is cancelled. Cancellation votes never
+        // close, their weight is taken back below instead
+        voted_to_cancel = contributor_account_state.voted_to_cancel();
+        if !cancelled && !voted_to_cancel && contributor_account_state.locked_until() > now {
+            return Err(FundraiserError::PositionLocked.into());
+        }
+        contributed = contributor_account_state.amount();
+        tier = contributor_account_state.tier();
+        matched = contributor_account_state.matched();
     }
 
-    // Validate vault
-    if !vault.is_writable() {
-        return Err(ProgramError::Immutable);
+    // Share of the vault owned by the contributor, out of how much in total, and the
+    // normalized amount that gets removed from the raise with it
+    let mut owned = contributed;
+    let mut total = current_amount;
+    let mut credited = contributed;
+    let mut accepted = None;
+    let mut remaining = remaining.iter();
+
+    // Multi-mint refunds are made one currency at a time, from the vault of `mint_to_raise`
+    if multi_mint {
+        let accepted_mints = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let seed = &[ACCEPTED_MINTS_SEED, fundraiser.key().as_ref()];
+        let (pda_accepted_mints, _) = pubkey::find_program_address(seed, &crate::ID);
+        if pda_accepted_mints.ne(accepted_mints.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !accepted_mints.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let index = {
+            let data = &mut accepted_mints.try_borrow_mut_data()?;
+            let accepted_state = load_acc_data_mut_unchecked::<AcceptedMintsData>(data)?;
+            let index = accepted_state
+                .find(mint_to_raise.key())
+                .ok_or(FundraiserError::MintNotAccepted)?;
+            total = accepted_state.mints[index].deposited();
+            index
+        };
+
+        let data = &mut contributor_account.try_borrow_mut_data()?;
+        let deposit = load_acc_data_mut_unchecked::<ContributorData>(data)?.deposits[index];
+        owned = deposit.raw();
+        credited = deposit.credited();
+        mint = *mint_to_raise.key();
+        accepted = Some((accepted_mints, index));
     }
 
-    if !vault.is_owned_by(&token_program.key()) {
-        return Err(ProgramError::IllegalOwner);
+    if owned == 0 {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    let removed = credited
+        .checked_add(matched)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // A refunded backer gives their tier slot back
+    let tiers = match tier {
+        Some(index) => {
+            let tiers = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let seed = &[TIERS_SEED, fundraiser.key().as_ref()];
+            let (pda_tiers, _) = pubkey::find_program_address(seed, &crate::ID);
+            if pda_tiers.ne(tiers.key()) {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            if !tiers.is_owned_by(&crate::ID) {
+                return Err(ProgramError::IllegalOwner);
+            }
+            if !tiers.is_writable() {
+                return Err(ProgramError::Immutable);
+            }
+            Some((tiers, index))
+        }
+        None => None,
+    };
+
+    // The sponsor's match of a refunded contribution goes back to the matching pool
+    let matching = if matched > 0 {
+        let matching_pool = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let pool_vault = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        check_matching_pool(fundraiser, matching_pool, pool_vault, &mint)?;
+        Some(pool_vault)
+    } else {
+        None
+    };
+
+    let round = match round {
+        Some(expected_round) => {
+            let round = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            Some((round, expected_round))
+        }
+        None => None,
+    };
+
+    let campaign = match campaign {
+        Some(expected_campaign) => {
+            let campaign = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            Some((campaign, expected_campaign))
+        }
+        None => None,
+    };
+
+    // The refunded gift is taken back from the beneficiaries it was allocated to
+    let allocations = if allocation {
+        let allocations = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let seed = &[ALLOCATIONS_SEED, fundraiser.key().as_ref()];
+        let (pda_allocations, _) = pubkey::find_program_address(seed, &crate::ID);
+        if pda_allocations.ne(allocations.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !allocations.is_owned_by(&crate::ID) {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Some(allocations)
+    } else {
+        None
+    };
+
+    // Validate vault
+    if native {
+        check_native_vault(fundraiser, vault)?;
+    } else {
+        check_token_vault(fundraiser, vault, &mint)?;
     }
 
+    // Tranches or vested funds may already have been paid out to the maker, so each
+    // contributor gets their share of what is left in the vault rather than the raw
+    // amount they put in
+    let vault_remaining = vault_balance(vault, native)?;
+    let amount_to_refund = pro_rata_share(owned, vault_remaining, total)?;
+    let match_to_return = pro_rata_share(matched, vault_remaining, total)?;
+
     let f_seed = [f_bump.to_le()];
     let seed = [
         Seed::from(FUNDRAISER_SEED),
@@ -106,16 +288,119 @@ pub fn process_refund_instruction(
         Seed::from(&f_seed),
     ];
 
-    let signer_seeds = Signer::from(&seed);
-    pinocchio_token::instructions::Transfer {
-        from: &vault,
-        to: &contributor_ata,
-        amount: amount_to_refund,
-        authority: &fundraiser,
+    // native refunds are paid back to the contributor's wallet
+    let destination = if native { contributor } else { contributor_ata };
+    transfer_from_vault(
+        vault,
+        destination,
+        fundraiser,
+        &seed,
+        amount_to_refund,
+        native,
+    )?;
+
+    if let Some(pool_vault) = matching {
+        transfer_from_vault(vault, pool_vault, fundraiser, &seed, match_to_return, false)?;
     }
-    .invoke_signed(&[signer_seeds])?;
 
     msg!("Transfer successfull");
 
+    // The receipt tokens of the refunded contribution are burned by their holder
+    if receipt {
+        let receipt_mint = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let receipt_account = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        check_receipt_mint(fundraiser, receipt_mint)?;
+
+        Burn {
+            account: receipt_account,
+            mint: receipt_mint,
+            authority: contributor,
+            amount: credited,
+        }
+        .invoke()?;
+    }
+
+    // A cancellation vote leaves with the money it was cast with
+    if voted_to_cancel && !cancelled {
+        let cancel_proposal = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let cancel_vote_record = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        withdraw_cancel_vote(
+            fundraiser,
+            contributor,
+            cancel_proposal,
+            cancel_vote_record,
+            credited,
+        )?;
+    }
+
+    // wSOL refunds can land in a temporary token account that is closed right away
+    if unwrap && !native {
+        unwrap_sol(contributor_ata, contributor, &mint)?;
+    }
+
+    let shares = {
+        let data = &mut contributor_account.try_borrow_mut_data()?;
+        let contributor_account_state = load_acc_data_mut_unchecked::<ContributorData>(data)?;
+        if let Some(allocations) = allocations {
+            let data = &mut allocations.try_borrow_mut_data()?;
+            load_acc_data_mut_unchecked::<AllocationsData>(data)?
+                .remove(&contributor_account_state.allocated());
+            contributor_account_state.set_allocated(&[0; MAX_BENEFICIARIES]);
+        }
+        let shares = contributor_account_state.shares_of(credited)?;
+        contributor_account_state
+            .set_shares(contributor_account_state.shares().saturating_sub(shares));
+        contributor_account_state.set_amount(contributed.saturating_sub(credited));
+        if let Some((_, index)) = accepted {
+            contributor_account_state.deposits[index] = MintDeposit::default();
+        }
+        contributor_account_state.set_tier(None);
+        contributor_account_state.set_matched(0);
+        shares
+    };
+
+    // a refund before the round's `ends_at` removes the amount from the fundraiser's round
+    // weight, after it the weights are frozen and the refund leaves the match unchanged
+    if let Some((round, expected_round)) = round {
+        update_round_weight(
+            round,
+            &expected_round,
+            fundraiser.key(),
+            contributed,
+            contributed.saturating_sub(credited),
+        )?;
+    }
+
+    if let Some((campaign, expected_campaign)) = campaign {
+        record_in_campaign(
+            campaign,
+            &expected_campaign,
+            0,
+            removed,
+            false,
+            contributed <= credited,
+        )?;
+    }
+
+    if let Some((tiers, index)) = tiers {
+        let data = &mut tiers.try_borrow_mut_data()?;
+        load_acc_data_mut_unchecked::<TiersData>(data)?.release(index)?;
+    }
+
+    if let Some((accepted_mints, index)) = accepted {
+        let data = &mut accepted_mints.try_borrow_mut_data()?;
+        let entry = &mut load_acc_data_mut_unchecked::<AcceptedMintsData>(data)?.mints[index];
+        entry.set_deposited(total.saturating_sub(owned));
+    }
+
+    {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        fundraiser_state.set_current_amount(current_amount.saturating_sub(removed));
+        fundraiser_state
+            .set_matched_amount(fundraiser_state.matched_amount().saturating_sub(matched));
+        fundraiser_state.set_total_shares(fundraiser_state.total_shares().saturating_sub(shares));
+    }
+
     Ok(())
 }
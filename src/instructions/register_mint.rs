@@ -0,0 +1,98 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{check_signer, load_acc_data_mut_unchecked, load_ix_data};
+use crate::state::{
+    ACCEPTED_MINTS_SEED, AcceptedMintIxData, AcceptedMintsData, FLAG_MULTI_MINT, FUNDRAISER_SEED,
+    FundraiserData, FundraiserStatus,
+};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::{ProgramResult, msg, pubkey};
+use pinocchio_associated_token_account::instructions::Create as Create_ATA;
+use pinocchio_token::state::Mint;
+
+/// Adds a mint, with its fixed conversion rate, to a multi-mint fundraiser and creates
+/// the vault holding it.
+pub fn process_register_mint_instruction(
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        maker,          // mut signer
+        mint,           // mint to accept
+        fundraiser,     // seeds = [b"fundraiser", maker.key().as_ref()]
+        accepted_mints, // mut. seeds = [b"accepted_mints", fundraiser.key().as_ref()]
+        vault,          // mut ata init. authority = fundraiser
+        system_program,
+        token_program,
+        _associated_token_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(maker)?;
+
+    let ix_data = load_ix_data::<AcceptedMintIxData>(instruction_data)?;
+
+    // validate fundraiser PDA
+    let seed = &[FUNDRAISER_SEED, maker.key().as_ref()];
+    let (pda_fundraiser, _) = pubkey::find_program_address(seed, &crate::ID);
+    if pda_fundraiser.ne(fundraiser.key()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        if fundraiser_state.status()? != FundraiserStatus::Active {
+            return Err(FundraiserError::FundraiserNotActive.into());
+        }
+        if !fundraiser_state.has_flag(FLAG_MULTI_MINT) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    // validate accepted_mints PDA
+    let seed = &[ACCEPTED_MINTS_SEED, fundraiser.key().as_ref()];
+    let (pda_accepted_mints, _) = pubkey::find_program_address(seed, &crate::ID);
+    if pda_accepted_mints.ne(accepted_mints.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !accepted_mints.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+    if !accepted_mints.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if !Mint::from_account_info(mint)?.is_initialized() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    {
+        let data = &mut accepted_mints.try_borrow_mut_data()?;
+        let accepted_state = load_acc_data_mut_unchecked::<AcceptedMintsData>(data)?;
+        ix_data.register(accepted_state, mint.key())?;
+    }
+
+    Create_ATA {
+        funding_account: maker,
+        account: vault,
+        wallet: fundraiser,
+        mint,
+        system_program,
+        token_program,
+    }
+    .invoke()?;
+
+    msg!("Mint registered");
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{
+    check_ed25519_signature, check_signer, create_pda_account, credit_plain_contribution,
+    load_acc_data_mut_unchecked, load_ix_data,
+};
+use crate::state::{ContributionIntent, ContributorData, FUNDRAISER_SEED, NONCE_SEED, NonceData};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::{Seed, Signer};
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+use pinocchio::{ProgramResult, msg, pubkey};
+use pinocchio_token::instructions::Transfer;
+use pinocchio_token::state::TokenAccount;
+
+/// Contributes on behalf of a contributor who signed a `ContributionIntent` off-chain.
+/// The relayer submits it right after an Ed25519 program instruction verifying that
+/// signature, and pays the fees and the rent of the contributor and nonce PDAs. The
+/// tokens are pulled from the contributor's token account, which approves the
+/// fundraiser PDA as delegate.
+pub fn process_relay_contribution_instruction(
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        relayer,             // mut signer
+        contributor,         // wallet that signed the intent
+        fundraiser,          // mut
+        contributor_account, // mut init_if_needed. seeds = [b"contributor", fundraiser.key().as_ref(), contributor.key().as_ref()]
+        contributor_ata,     // mut. delegate = fundraiser
+        vault,               // mut
+        nonce, // mut init. seeds = [b"nonce", contributor.key().as_ref(), intent.nonce.as_ref()]
+        instructions_sysvar,
+        _token_program,
+        _system_program,
+        remaining @ .., // block record PDA (once anyone is blocked)
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(relayer)?;
+
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let intent = load_ix_data::<ContributionIntent>(instruction_data)?;
+    check_ed25519_signature(instructions_sysvar, contributor.key(), instruction_data)?;
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    if now > intent.expiry() {
+        return Err(FundraiserError::IntentExpired.into());
+    }
+    if intent.fundraiser().ne(fundraiser.key()) || intent.amount() == 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // the nonce PDA can only be created once, so the intent cannot be replayed
+    let nonce_bytes = intent.nonce;
+    let seeds: &[&[u8]] = &[NONCE_SEED, contributor.key(), &nonce_bytes];
+    let (nonce_pda, n_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if nonce_pda.ne(nonce.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !nonce.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let n_seed = [n_bump];
+    let seed = [
+        Seed::from(NONCE_SEED),
+        Seed::from(contributor.key()),
+        Seed::from(&nonce_bytes),
+        Seed::from(&n_seed),
+    ];
+    create_pda_account::<NonceData>(relayer, nonce, &seed)?;
+    {
+        let data = &mut nonce.try_borrow_mut_data()?;
+        load_acc_data_mut_unchecked::<NonceData>(data)?.set_bump(n_bump);
+    }
+
+    let seeds: &[&[u8]] = &[b"contributor", fundraiser.key(), contributor.key()];
+    let (contributor_pda, c_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if contributor_pda.ne(contributor_account.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if contributor_account.data_is_empty() {
+        let c_seed = [c_bump];
+        let seed = [
+            Seed::from(b"contributor"),
+            Seed::from(fundraiser.key()),
+            Seed::from(contributor.key()),
+            Seed::from(&c_seed),
+        ];
+        create_pda_account::<ContributorData>(relayer, contributor_account, &seed)?;
+    }
+
+    // the fundraiser may only move the tokens of the wallet that signed
+    {
+        let source = TokenAccount::from_account_info(contributor_ata)?;
+        if source.owner().ne(contributor.key()) || source.delegate() != Some(fundraiser.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    let (maker, f_bump) = credit_plain_contribution(
+        fundraiser,
+        contributor_account,
+        contributor.key(),
+        vault,
+        remaining,
+        intent.amount(),
+        now,
+    )?;
+
+    let f_seed = [f_bump];
+    let seed = [
+        Seed::from(FUNDRAISER_SEED),
+        Seed::from(&maker),
+        Seed::from(&f_seed),
+    ];
+    Transfer {
+        from: contributor_ata,
+        to: vault,
+        authority: fundraiser,
+        amount: intent.amount(),
+    }
+    .invoke_signed(&[Signer::from(&seed)])?;
+
+    msg!("Relayed contribution recorded");
+
+    Ok(())
+}
@@ -0,0 +1,330 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{
+    check_signer, create_pda_account, load_acc_data_mut_unchecked, load_ix_data, quadratic_match,
+};
+use crate::state::{
+    CreateRoundIxData, DistributeRoundIxData, FLAG_NATIVE, FLAG_PLEDGE, FundraiserData,
+    FundraiserStatus, MAX_ROUND_PROJECTS, ROUND_SEED, RoundData,
+};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::{Seed, Signer};
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+use pinocchio::{ProgramResult, msg, pubkey};
+use pinocchio_associated_token_account::instructions::Create as Create_ATA;
+use pinocchio_token::instructions::Transfer;
+use pinocchio_token::state::{Mint, TokenAccount};
+
+/// Opens a quadratic funding round in `mint`. Its pool is whatever is sent to the
+/// round vault before the round is finalized. The round is keyed by an id chosen by the
+/// admin, who opens a new round under a new id.
+pub fn process_create_round_instruction(
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        admin,       // mut signer
+        round,       // mut init. seeds = [b"round", admin.key().as_ref(), id.to_le_bytes()]
+        mint,        // mint
+        round_vault, // mut ata init. authority = round
+        system_program,
+        token_program,
+        _associated_token_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(admin)?;
+
+    let ix_data = load_ix_data::<CreateRoundIxData>(instruction_data)?;
+    if ix_data.ends_at() <= Clock::get()?.unix_timestamp as u64 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let id_bytes = ix_data.id;
+    let seeds: &[&[u8]] = &[ROUND_SEED, admin.key(), &id_bytes];
+    let (round_pda, r_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if round_pda.ne(round.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !round.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    if !Mint::from_account_info(mint)?.is_initialized() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let r_seed = [r_bump];
+    let seed = [
+        Seed::from(ROUND_SEED),
+        Seed::from(admin.key()),
+        Seed::from(&id_bytes),
+        Seed::from(&r_seed),
+    ];
+    create_pda_account::<RoundData>(admin, round, &seed)?;
+
+    {
+        let data = &mut round.try_borrow_mut_data()?;
+        let round_state = load_acc_data_mut_unchecked::<RoundData>(data)?;
+        round_state.set_admin(admin.key());
+        round_state.set_mint(mint.key());
+        round_state.set_ends_at(ix_data.ends_at());
+        round_state.set_id(ix_data.id());
+        round_state.set_bump(r_bump);
+        round_state.add_padding();
+    }
+
+    // sponsors fund the pool with plain token transfers
+    Create_ATA {
+        funding_account: admin,
+        account: round_vault,
+        wallet: round,
+        mint: mint,
+        system_program: system_program,
+        token_program: token_program,
+    }
+    .invoke()?;
+
+    msg!("Round created");
+
+    Ok(())
+}
+
+/// Enters a fundraiser into a round. Only fundraisers raising the round's mint that
+/// have not received contributions yet can join, so every contribution is counted.
+pub fn process_join_round_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        maker,      // signer
+        fundraiser, // mut
+        round,      // mut
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(maker)?;
+
+    if fundraiser.data_is_empty() || round.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) || !round.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let data = &mut fundraiser.try_borrow_mut_data()?;
+    let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+    if fundraiser_state.maker().ne(maker.key()) {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+    if fundraiser_state.status()? != FundraiserStatus::Active {
+        return Err(FundraiserError::FundraiserNotActive.into());
+    }
+    if fundraiser_state.in_round() || fundraiser_state.current_amount() > 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // weights are computed from raw amounts of the round's mint, actually paid in
+    if fundraiser_state.has_flag(FLAG_NATIVE)
+        || fundraiser_state.has_flag(FLAG_PLEDGE)
+        || fundraiser_state.is_normalized()
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let round_data = &mut round.try_borrow_mut_data()?;
+    let round_state = load_acc_data_mut_unchecked::<RoundData>(round_data)?;
+    if round_state.finalized() || Clock::get()?.unix_timestamp as u64 >= round_state.ends_at() {
+        return Err(FundraiserError::RoundClosed.into());
+    }
+    if round_state.mint().ne(&fundraiser_state.mint_to_raise()) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let count = round_state.project_count();
+    if count as usize >= MAX_ROUND_PROJECTS {
+        return Err(FundraiserError::RoundFull.into());
+    }
+
+    let project = &mut round_state.projects[count as usize];
+    project.set_fundraiser(fundraiser.key());
+    project.set_maker(maker.key());
+    round_state.set_project_count(count + 1);
+
+    fundraiser_state.set_round(round.key());
+
+    msg!("Fundraiser joined the round");
+
+    Ok(())
+}
+
+/// Fixes each project's match once the round has ended. The pool is split in
+/// proportion to the square of each project's sum of square roots of contributions.
+/// Anyone can finalize.
+pub fn process_finalize_round_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        round,       // mut
+        round_vault, // ata. authority = round
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if round.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !round.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let data = &mut round.try_borrow_mut_data()?;
+    let round_state = load_acc_data_mut_unchecked::<RoundData>(data)?;
+    if round_state.finalized() {
+        return Err(FundraiserError::RoundClosed.into());
+    }
+    if (Clock::get()?.unix_timestamp as u64) < round_state.ends_at() {
+        return Err(FundraiserError::RoundNotEnded.into());
+    }
+
+    let pool = {
+        let vault_account = TokenAccount::from_account_info(round_vault)?;
+        if vault_account.owner().ne(round.key()) || vault_account.mint().ne(&round_state.mint()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        vault_account.amount()
+    };
+
+    let count = round_state.project_count() as usize;
+    let mut total_weight: u128 = 0;
+    for project in round_state.projects[..count].iter() {
+        total_weight = total_weight
+            .checked_add(project.weight())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+
+    // without any contribution nothing is matched
+    if total_weight > 0 {
+        for project in round_state.projects[..count].iter_mut() {
+            project.set_match_amount(quadratic_match(pool, project.weight(), total_weight)?);
+        }
+    }
+
+    round_state.set_pool_amount(pool);
+    round_state.set_finalized(true);
+
+    msg!("Round finalized");
+
+    Ok(())
+}
+
+/// Pays a project of a finalized round its match once its fundraiser succeeded. The
+/// match of a project whose fundraiser was cancelled or failed goes back to the round
+/// admin instead. Anyone can distribute.
+pub fn process_distribute_round_instruction(
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        round,       // mut
+        round_vault, // mut ata. authority = round
+        destination, // mut. token account of the project's maker, or of the admin
+        fundraiser,  // the project's fundraiser
+        _token_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if round.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !round.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let ix_data = load_ix_data::<DistributeRoundIxData>(instruction_data)?;
+    let index = ix_data.project() as usize;
+
+    let (amount, admin, id, r_bump) = {
+        let data = &mut round.try_borrow_mut_data()?;
+        let round_state = load_acc_data_mut_unchecked::<RoundData>(data)?;
+        if !round_state.finalized() {
+            return Err(FundraiserError::RoundNotEnded.into());
+        }
+        if index >= round_state.project_count() as usize {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (admin, mint, id, r_bump) = (
+            round_state.admin(),
+            round_state.mint(),
+            round_state.id,
+            round_state.bump(),
+        );
+        let project = &mut round_state.projects[index];
+        if fundraiser.key().ne(&project.fundraiser()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // the match is only paid once the project's outcome is final, a closed fundraiser
+        // paid its raise out to the maker
+        let refundable = if fundraiser.data_is_empty() {
+            false
+        } else {
+            if !fundraiser.is_owned_by(&crate::ID) {
+                return Err(ProgramError::IllegalOwner);
+            }
+            let now = Clock::get()?.unix_timestamp as u64;
+            let data = &mut fundraiser.try_borrow_mut_data()?;
+            let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+            if fundraiser_state.is_refundable(now)? {
+                true
+            } else if fundraiser_state.has_succeeded(now)? {
+                false
+            } else {
+                return Err(FundraiserError::OutcomePending.into());
+            }
+        };
+        let recipient = if refundable { admin } else { project.maker() };
+
+        let destination_account = TokenAccount::from_account_info(destination)?;
+        if destination_account.owner().ne(&recipient) || destination_account.mint().ne(&mint) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if project.paid() || project.match_amount() == 0 {
+            return Err(FundraiserError::NothingToClaim.into());
+        }
+        project.set_paid(true);
+
+        (project.match_amount(), admin, id, r_bump)
+    };
+
+    let r_seed = [r_bump];
+    let seed = [
+        Seed::from(ROUND_SEED),
+        Seed::from(&admin),
+        Seed::from(&id),
+        Seed::from(&r_seed),
+    ];
+    Transfer {
+        from: round_vault,
+        to: destination,
+        authority: round,
+        amount,
+    }
+    .invoke_signed(&[Signer::from(&seed)])?;
+
+    msg!("Round match distributed");
+
+    Ok(())
+}
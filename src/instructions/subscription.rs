@@ -0,0 +1,288 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{
+    check_signer, close_pda_account, create_pda_account, credit_plain_contribution,
+    load_acc_data_mut_unchecked, load_ix_data,
+};
+use crate::state::{
+    ContributorData, FundraiserData, FundraiserStatus, SUBSCRIPTION_SEED, SubscriptionData,
+    SubscriptionIxData,
+};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::{Seed, Signer};
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+use pinocchio::{ProgramResult, msg, pubkey};
+use pinocchio_token::instructions::Transfer;
+use pinocchio_token::state::TokenAccount;
+
+/// Sets up a recurring contribution. The contributor approves the subscription PDA as
+/// delegate of `source` for every period beforehand, and deposits the bounties of all
+/// periods with the subscription. The first payment is due right away.
+pub fn process_create_subscription_instruction(
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        contributor,         // mut signer
+        fundraiser,          //
+        contributor_account, // mut init_if_needed. seeds = [b"contributor", fundraiser.key().as_ref(), contributor.key().as_ref()]
+        subscription, // mut init. seeds = [b"subscription", fundraiser.key().as_ref(), contributor.key().as_ref()]
+        source,       // token account approving the subscription PDA
+        _system_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(contributor)?;
+
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let ix_data = load_ix_data::<SubscriptionIxData>(instruction_data)?;
+    if ix_data.amount() == 0 || ix_data.interval() == 0 || ix_data.periods() == 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let total = ix_data
+        .amount()
+        .checked_mul(ix_data.periods() as u64)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let bounties = ix_data
+        .bounty()
+        .checked_mul(ix_data.periods() as u64)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let mint = {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        let fundraiser_state = load_acc_data_mut_unchecked::<FundraiserData>(data)?;
+        if fundraiser_state.status()? != FundraiserStatus::Active {
+            return Err(FundraiserError::FundraiserNotActive.into());
+        }
+        if !fundraiser_state.takes_plain_transfers() {
+            return Err(FundraiserError::InvalidReleaseMode.into());
+        }
+        fundraiser_state.mint_to_raise()
+    };
+
+    let seeds: &[&[u8]] = &[SUBSCRIPTION_SEED, fundraiser.key(), contributor.key()];
+    let (subscription_pda, s_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if subscription_pda.ne(subscription.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !subscription.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    // every period has to be covered by the approval up-front
+    {
+        let source_account = TokenAccount::from_account_info(source)?;
+        if source_account.owner().ne(contributor.key())
+            || source_account.mint().ne(&mint)
+            || source_account.delegate() != Some(subscription.key())
+            || source_account.delegated_amount() < total
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    let seeds: &[&[u8]] = &[b"contributor", fundraiser.key(), contributor.key()];
+    let (contributor_pda, c_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if contributor_pda.ne(contributor_account.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if contributor_account.data_is_empty() {
+        let c_seed = [c_bump];
+        let seed = [
+            Seed::from(b"contributor"),
+            Seed::from(fundraiser.key()),
+            Seed::from(contributor.key()),
+            Seed::from(&c_seed),
+        ];
+        create_pda_account::<ContributorData>(contributor, contributor_account, &seed)?;
+    }
+
+    let s_seed = [s_bump];
+    let seed = [
+        Seed::from(SUBSCRIPTION_SEED),
+        Seed::from(fundraiser.key()),
+        Seed::from(contributor.key()),
+        Seed::from(&s_seed),
+    ];
+    create_pda_account::<SubscriptionData>(contributor, subscription, &seed)?;
+
+    {
+        let data = &mut subscription.try_borrow_mut_data()?;
+        let subscription_state = load_acc_data_mut_unchecked::<SubscriptionData>(data)?;
+        subscription_state.set_contributor(contributor.key());
+        subscription_state.set_source(source.key());
+        subscription_state.set_amount(ix_data.amount());
+        subscription_state.set_interval(ix_data.interval());
+        subscription_state.set_next_due(Clock::get()?.unix_timestamp as u64);
+        subscription_state.set_bounty(ix_data.bounty());
+        subscription_state.set_remaining(ix_data.periods());
+        subscription_state.set_bump(s_bump);
+        subscription_state.add_padding();
+    }
+
+    // crankers are paid out of the subscription's own lamports
+    if bounties > 0 {
+        pinocchio_system::instructions::Transfer {
+            from: contributor,
+            to: subscription,
+            lamports: bounties,
+        }
+        .invoke()?;
+    }
+
+    msg!("Subscription created");
+
+    Ok(())
+}
+
+/// Pays a due period of a subscription into the vault and the bounty to the cranker.
+/// Anyone can process a subscription. It is closed to the contributor after its last
+/// payment.
+pub fn process_subscription_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        cranker,             // mut signer
+        contributor,         // mut. receives the subscription's lamports once it ends
+        fundraiser,          // mut
+        contributor_account, // mut. seeds = [b"contributor", fundraiser.key().as_ref(), contributor.key().as_ref()]
+        subscription, // mut. seeds = [b"subscription", fundraiser.key().as_ref(), contributor.key().as_ref()]
+        source,       // mut
+        vault,        // mut
+        _token_program,
+        remaining @ .., // block record PDA (once anyone is blocked)
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(cranker)?;
+
+    if subscription.data_is_empty() || contributor_account.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_owned_by(&crate::ID)
+        || !subscription.is_owned_by(&crate::ID)
+        || !contributor_account.is_owned_by(&crate::ID)
+    {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let seeds: &[&[u8]] = &[SUBSCRIPTION_SEED, fundraiser.key(), contributor.key()];
+    let (subscription_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if subscription_pda.ne(subscription.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let seeds: &[&[u8]] = &[b"contributor", fundraiser.key(), contributor.key()];
+    let (contributor_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if contributor_pda.ne(contributor_account.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    let subscription_state = {
+        let data = &mut subscription.try_borrow_mut_data()?;
+        *load_acc_data_mut_unchecked::<SubscriptionData>(data)?
+    };
+    if now < subscription_state.next_due() {
+        return Err(FundraiserError::SubscriptionNotDue.into());
+    }
+    if subscription_state.source().ne(source.key()) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let amount = subscription_state.amount();
+
+    credit_plain_contribution(
+        fundraiser,
+        contributor_account,
+        contributor.key(),
+        vault,
+        remaining,
+        amount,
+        now,
+    )?;
+
+    let s_seed = [subscription_state.bump()];
+    let seed = [
+        Seed::from(SUBSCRIPTION_SEED),
+        Seed::from(fundraiser.key()),
+        Seed::from(contributor.key()),
+        Seed::from(&s_seed),
+    ];
+    Transfer {
+        from: source,
+        to: vault,
+        authority: subscription,
+        amount,
+    }
+    .invoke_signed(&[Signer::from(&seed)])?;
+
+    // a late crank pays a single period, the schedule moves on from the due time
+    let remaining_periods = subscription_state.remaining() - 1;
+    {
+        let data = &mut subscription.try_borrow_mut_data()?;
+        let subscription_state = load_acc_data_mut_unchecked::<SubscriptionData>(data)?;
+        subscription_state
+            .set_next_due(subscription_state.next_due() + subscription_state.interval());
+        subscription_state.set_remaining(remaining_periods);
+    }
+
+    *subscription.try_borrow_mut_lamports()? -= subscription_state.bounty();
+    *cranker.try_borrow_mut_lamports()? += subscription_state.bounty();
+
+    if remaining_periods == 0 {
+        close_pda_account(subscription, contributor)?;
+    }
+
+    msg!("Subscription processed");
+
+    Ok(())
+}
+
+/// Stops a subscription before its last period and returns its lamports, including
+/// the bounties of the periods left, to the contributor.
+pub fn process_cancel_subscription_instruction(
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        contributor,  // mut signer
+        fundraiser,   //
+        subscription, // mut close = contributor. seeds = [b"subscription", fundraiser.key().as_ref(), contributor.key().as_ref()]
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(contributor)?;
+
+    if subscription.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !subscription.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let seeds: &[&[u8]] = &[SUBSCRIPTION_SEED, fundraiser.key(), contributor.key()];
+    let (subscription_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if subscription_pda.ne(subscription.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    close_pda_account(subscription, contributor)?;
+
+    msg!("Subscription cancelled");
+
+    Ok(())
+}
@@ -0,0 +1,326 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{
+    check_allowlist_proof, check_attestation, check_not_blocked, check_receipt_mint, check_signer,
+    check_token_gate, create_pda_account, load_acc_data_mut_unchecked, mul_div, record_in_campaign,
+    split_ix_data,
+};
+use crate::state::{
+    ContributorData, FLAG_ALLOCATION, FLAG_ALLOWLIST, FLAG_ATTESTATION, FLAG_CAMPAIGN,
+    FLAG_MULTI_MINT, FLAG_PLEDGE, FLAG_RECEIPT, FLAG_TOKEN_GATE, FundraiserData,
+    MAX_ACCEPTED_MINTS, MAX_BENEFICIARIES, MintDeposit, TransferPositionIxData,
+};
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::Seed;
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::{Sysvar, clock::Clock};
+use pinocchio::{ProgramResult, msg, pubkey};
+use pinocchio_token::instructions::Transfer;
+use pinocchio_token::state::TokenAccount;
+
+/// Moves part or all of a contribution to another wallet. The raise itself is unchanged,
+/// the recipient simply takes over the claim on refunds and rewards. Positions can move
+/// while the raise is running as well as after it succeeded, but not once it is
+/// refundable, since refunds are paid to whoever holds the position.
+pub fn process_transfer_position_instruction(
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [
+        holder,            // mut signer
+        recipient,         //
+        fundraiser,        //
+        holder_account, // mut. seeds = [b"contributor", fundraiser.key().as_ref(), holder.key().as_ref()]
+        recipient_account, // mut init_if_needed. seeds = [b"contributor", fundraiser.key().as_ref(), recipient.key().as_ref()]
+        _system_program,
+        _token_program,
+        // in order: recipient gate token account (FLAG_TOKEN_GATE), recipient block record
+        // PDA (once anyone is blocked), recipient attestation PDA (FLAG_ATTESTATION),
+        // campaign PDA (FLAG_CAMPAIGN), receipt mint PDA and the holder's and recipient's
+        // receipt token accounts (FLAG_RECEIPT)
+        remaining @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(holder)?;
+
+    if holder.key().eq(recipient.key()) {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    if fundraiser.data_is_empty() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let (ix_data, extra_data) = split_ix_data::<TransferPositionIxData>(instruction_data)?;
+    let amount = ix_data.amount();
+    if amount == 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let fundraiser_state = {
+        let data = &mut fundraiser.try_borrow_mut_data()?;
+        *load_acc_data_mut_unchecked::<FundraiserData>(data)?
+    };
+    if fundraiser_state.is_refundable(Clock::get()?.unix_timestamp as u64)? {
+        return Err(FundraiserError::FundraiserNotActive.into());
+    }
+    // splitting a position across wallets would raise the fundraiser's quadratic match
+    if fundraiser_state.in_round() {
+        return Err(FundraiserError::PositionInRound.into());
+    }
+    // a pledge is collected from the token account its holder approved
+    if fundraiser_state.has_flag(FLAG_PLEDGE) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // validate both contributor PDAs
+    let seeds: &[&[u8]] = &[b"contributor", fundraiser.key(), holder.key()];
+    let (holder_account_pda, _) = pubkey::find_program_address(seeds, &crate::ID);
+    if holder_account_pda.ne(holder_account.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !holder_account.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if !holder_account.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+
+    let seeds: &[&[u8]] = &[b"contributor", fundraiser.key(), recipient.key()];
+    let (recipient_account_pda, r_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if recipient_account_pda.ne(recipient_account.key()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !recipient_account.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+
+    // The recipient has to qualify as a contributor of the fundraiser on their own
+    let mut cap = 0;
+    let mut extra_data = extra_data;
+    if fundraiser_state.has_flag(FLAG_ALLOWLIST) {
+        let (proof_cap, rest) =
+            check_allowlist_proof(recipient.key(), &fundraiser_state.merkle_root(), extra_data)?;
+        extra_data = rest;
+        cap = proof_cap;
+    }
+
+    if !extra_data.is_empty() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let mut remaining = remaining.iter();
+
+    if fundraiser_state.has_flag(FLAG_TOKEN_GATE) {
+        let gate_account = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        check_token_gate(
+            &fundraiser_state.gate_mint(),
+            fundraiser_state.gate_min_balance(),
+            recipient.key(),
+            gate_account,
+        )?;
+    }
+
+    if fundraiser_state.blocked_count() > 0 {
+        let block_record = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        check_not_blocked(fundraiser, recipient.key(), block_record)?;
+    }
+
+    if fundraiser_state.has_flag(FLAG_ATTESTATION) {
+        let attestation = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        check_attestation(&fundraiser_state.issuer(), recipient.key(), attestation)?;
+    }
+
+    let campaign = if fundraiser_state.has_flag(FLAG_CAMPAIGN) {
+        Some(remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?)
+    } else {
+        None
+    };
+
+    if recipient_account.data_is_empty() || recipient_account.lamports() == 0 {
+        let r_seed = [r_bump];
+        let seed = [
+            Seed::from(b"contributor"),
+            Seed::from(fundraiser.key()),
+            Seed::from(recipient.key()),
+            Seed::from(&r_seed),
+        ];
+        create_pda_account::<ContributorData>(holder, recipient_account, &seed)?;
+    } else if !recipient_account.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let (deposits, allocated, tier, shares, matched, holder_left) = {
+        let data = &mut holder_account.try_borrow_mut_data()?;
+        let holder_state = load_acc_data_mut_unchecked::<ContributorData>(data)?;
+
+        if holder_state.locked_until() > Clock::get()?.unix_timestamp as u64 {
+            return Err(FundraiserError::PositionLocked.into());
+        }
+        if holder_state.amount() < amount {
+            return Err(ProgramError::InsufficientFunds);
+        }
+        if holder_state.reward_claimed() {
+            return Err(FundraiserError::RewardAlreadyClaimed.into());
+        }
+
+        // multi-mint positions are refunded per currency, which only splits cleanly
+        // when the whole position moves
+        let deposits = holder_state.deposits;
+        if fundraiser_state.has_flag(FLAG_MULTI_MINT) {
+            if holder_state.amount() != amount {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            holder_state.deposits = [MintDeposit::default(); MAX_ACCEPTED_MINTS];
+        }
+
+        // so do the amounts a donor directed to each beneficiary
+        let allocated = holder_state.allocated();
+        if fundraiser_state.has_flag(FLAG_ALLOCATION) {
+            if holder_state.amount() != amount {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            holder_state.set_allocated(&[0; MAX_BENEFICIARIES]);
+        }
+
+        // a tier belongs to a single backer, it only changes hands with the whole position
+        let tier = holder_state.tier();
+        if tier.is_some() {
+            if holder_state.amount() != amount {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            holder_state.set_tier(None);
+        }
+
+        // shares go along pro-rata, keeping any early-bird bonus with the moved amount
+        let shares = holder_state.shares_of(amount)?;
+        holder_state.set_shares(holder_state.shares() - shares);
+
+        // so does the sponsor's match, which is returned to the pool if it gets refunded
+        let matched = if amount == holder_state.amount() {
+            holder_state.matched()
+        } else {
+            mul_div(holder_state.matched(), amount, holder_state.amount())?
+        };
+        holder_state.set_matched(holder_state.matched() - matched);
+        holder_state.set_amount(holder_state.amount() - amount);
+        (
+            deposits,
+            allocated,
+            tier,
+            shares,
+            matched,
+            holder_state.amount() == 0,
+        )
+    };
+
+    let recipient_joined = {
+        let data = &mut recipient_account.try_borrow_mut_data()?;
+        let recipient_state = load_acc_data_mut_unchecked::<ContributorData>(data)?;
+        if recipient_state.reward_claimed() {
+            return Err(FundraiserError::RewardAlreadyClaimed.into());
+        }
+        let recipient_joined = recipient_state.amount() == 0;
+
+        let received = recipient_state
+            .amount()
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if cap > 0 && received > cap {
+            return Err(FundraiserError::ContributionCapExceeded.into());
+        }
+        recipient_state.set_amount(received);
+        recipient_state.set_shares(
+            recipient_state
+                .shares()
+                .checked_add(shares)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+        recipient_state.set_matched(
+            recipient_state
+                .matched()
+                .checked_add(matched)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+
+        if tier.is_some() {
+            if recipient_state.tier().is_some() {
+                return Err(FundraiserError::TierAlreadySelected.into());
+            }
+            recipient_state.set_tier(tier);
+        }
+
+        if fundraiser_state.has_flag(FLAG_MULTI_MINT) {
+            for (deposit, moved) in recipient_state.deposits.iter_mut().zip(deposits.iter()) {
+                deposit.set_raw(
+                    deposit
+                        .raw()
+                        .checked_add(moved.raw())
+                        .ok_or(ProgramError::ArithmeticOverflow)?,
+                );
+                deposit.set_credited(
+                    deposit
+                        .credited()
+                        .checked_add(moved.credited())
+                        .ok_or(ProgramError::ArithmeticOverflow)?,
+                );
+            }
+        }
+
+        if fundraiser_state.has_flag(FLAG_ALLOCATION) {
+            let mut received = recipient_state.allocated();
+            for (total, moved) in received.iter_mut().zip(allocated.iter()) {
+                *total = total
+                    .checked_add(*moved)
+                    .ok_or(ProgramError::ArithmeticOverflow)?;
+            }
+            recipient_state.set_allocated(&received);
+        }
+
+        recipient_joined
+    };
+
+    // the raise is unchanged, only who backs it
+    if let Some(campaign) = campaign {
+        record_in_campaign(
+            campaign,
+            &fundraiser_state.campaign(),
+            0,
+            0,
+            recipient_joined,
+            holder_left,
+        )?;
+    }
+
+    // Receipt tokens follow the position so refunds can still burn them
+    if fundraiser_state.has_flag(FLAG_RECEIPT) {
+        let receipt_mint = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let holder_receipt = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let recipient_receipt = remaining.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        check_receipt_mint(fundraiser, receipt_mint)?;
+
+        if TokenAccount::from_account_info(holder_receipt)?
+            .mint()
+            .ne(receipt_mint.key())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Transfer {
+            from: holder_receipt,
+            to: recipient_receipt,
+            authority: holder,
+            amount,
+        }
+        .invoke()?;
+    }
+
+    msg!("Position transferred");
+
+    Ok(())
+}
@@ -40,12 +40,83 @@ pub fn process_instruction(
         FundraiserInstructions::Refund => {
             instructions::process_refund_instruction(accounts, data)?;
         }
-        _ => {
-            pinocchio_log::log!(
-                "unknown instruction discriminator: {}",
-                discriminator.to_owned(),
-            );
-            return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+        FundraiserInstructions::Check => {
+            instructions::process_check_contributions_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::ClaimTranche => {
+            instructions::process_claim_tranche_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::Cancel => {
+            instructions::process_cancel_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::CastVote => {
+            instructions::process_cast_vote_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::ProposeCancel => {
+            instructions::process_propose_cancel_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::VoteCancel => {
+            instructions::process_vote_cancel_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::RegisterMint => {
+            instructions::process_register_mint_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::IssueAttestation => {
+            instructions::process_issue_attestation_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::Block => {
+            instructions::process_block_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::Unblock => {
+            instructions::process_unblock_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::TransferPosition => {
+            instructions::process_transfer_position_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::ClaimReward => {
+            instructions::process_claim_reward_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::FundMatchingPool => {
+            instructions::process_fund_matching_pool_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::WithdrawMatchingPool => {
+            instructions::process_withdraw_matching_pool_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::CreateRound => {
+            instructions::process_create_round_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::JoinRound => {
+            instructions::process_join_round_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::FinalizeRound => {
+            instructions::process_finalize_round_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::DistributeRound => {
+            instructions::process_distribute_round_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::CreateCampaign => {
+            instructions::process_create_campaign_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::Collect => {
+            instructions::process_collect_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::CreateSubscription => {
+            instructions::process_create_subscription_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::ProcessSubscription => {
+            instructions::process_subscription_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::CancelSubscription => {
+            instructions::process_cancel_subscription_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::RelayContribution => {
+            instructions::process_relay_contribution_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::CloseRewards => {
+            instructions::process_close_rewards_instruction(accounts, data)?;
+        }
+        FundraiserInstructions::ReopenVote => {
+            instructions::process_reopen_vote_instruction(accounts, data)?;
         }
     }
 
@@ -0,0 +1,170 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{DataLen, mul_div};
+use crate::state::MAX_ACCEPTED_MINTS;
+use bytemuck::{Pod, Zeroable};
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::Pubkey;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct AcceptedMint {
+    pub mint: [u8; 32],
+    pub rate: [u8; 8],
+    pub deposited: [u8; 8],
+    pub rate_decimals: [u8; 1],
+    _padding: [u8; 7],
+}
+
+impl AcceptedMint {
+    pub fn set_mint(&mut self, mint: &Pubkey) {
+        self.mint.copy_from_slice(mint.as_ref());
+    }
+
+    pub fn mint(&self) -> Pubkey {
+        Pubkey::from(self.mint)
+    }
+
+    pub fn set_rate(&mut self, rate: u64) {
+        self.rate = rate.to_le_bytes();
+    }
+
+    /// Normalized units credited per `10^rate_decimals` raw units of the mint.
+    pub fn rate(&self) -> u64 {
+        u64::from_le_bytes(self.rate)
+    }
+
+    pub fn set_rate_decimals(&mut self, decimals: u8) {
+        self.rate_decimals = decimals.to_le_bytes();
+    }
+
+    pub fn rate_decimals(&self) -> u8 {
+        u8::from_le_bytes(self.rate_decimals)
+    }
+
+    pub fn set_deposited(&mut self, amount: u64) {
+        self.deposited = amount.to_le_bytes();
+    }
+
+    /// Raw amount of the mint contributed and not refunded yet.
+    pub fn deposited(&self) -> u64 {
+        u64::from_le_bytes(self.deposited)
+    }
+
+    /// Converts a raw amount of this mint into the fundraiser's normalized unit, rounding down.
+    pub fn to_normalized(&self, raw: u64) -> Result<u64, ProgramError> {
+        let scale = 10u64
+            .checked_pow(self.rate_decimals() as u32)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        mul_div(raw, self.rate(), scale)
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 7];
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct AcceptedMintsData {
+    pub count: [u8; 1],
+    pub bump: [u8; 1],
+    _padding: [u8; 6],
+    pub mints: [AcceptedMint; MAX_ACCEPTED_MINTS],
+}
+
+impl AcceptedMintsData {
+    pub fn set_count(&mut self, count: u8) {
+        self.count = count.to_le_bytes();
+    }
+
+    pub fn count(&self) -> u8 {
+        u8::from_le_bytes(self.count)
+    }
+
+    pub fn set_bump(&mut self, bump: u8) {
+        self.bump = bump.to_le_bytes();
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+
+    /// Index of `mint` among the registered mints, the fundraiser's own mint being index 0.
+    pub fn find(&self, mint: &Pubkey) -> Option<usize> {
+        self.mints[..self.count() as usize]
+            .iter()
+            .position(|entry| entry.mint.eq(mint))
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 6];
+    }
+}
+
+impl DataLen for AcceptedMintsData {
+    const LEN: usize = core::mem::size_of::<AcceptedMintsData>();
+}
+
+/// Conversion rate of a mint, passed to RegisterMint and as the trailing section of
+/// the initialize instruction data when `FLAG_MULTI_MINT` is set.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct AcceptedMintIxData {
+    pub rate: [u8; 8],
+    pub rate_decimals: [u8; 1],
+    _padding: [u8; 7],
+}
+
+impl DataLen for AcceptedMintIxData {
+    const LEN: usize = core::mem::size_of::<AcceptedMintIxData>();
+}
+
+impl AcceptedMintIxData {
+    pub fn rate(&self) -> u64 {
+        u64::from_le_bytes(self.rate)
+    }
+
+    pub fn set_rate(&mut self, rate: u64) {
+        self.rate = rate.to_le_bytes();
+    }
+
+    pub fn rate_decimals(&self) -> u8 {
+        u8::from_le_bytes(self.rate_decimals)
+    }
+
+    pub fn set_rate_decimals(&mut self, decimals: u8) {
+        self.rate_decimals = decimals.to_le_bytes();
+    }
+
+    pub fn set_padding(&mut self) {
+        self._padding = [0; 7];
+    }
+
+    /// Registers the mint as the next entry of the accepted mints.
+    pub fn register(
+        &self,
+        accepted: &mut AcceptedMintsData,
+        mint: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        if self.rate() == 0 || 10u64.checked_pow(self.rate_decimals() as u32).is_none() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        if accepted.find(mint).is_some() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let index = accepted.count() as usize;
+        let entry = accepted
+            .mints
+            .get_mut(index)
+            .ok_or(FundraiserError::TooManyMints)?;
+        entry.set_mint(mint);
+        entry.set_rate(self.rate());
+        entry.set_rate_decimals(self.rate_decimals());
+        entry.set_deposited(0);
+        entry.add_padding();
+        accepted.set_count(index as u8 + 1);
+
+        Ok(())
+    }
+}
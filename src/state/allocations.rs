@@ -0,0 +1,183 @@
+use crate::errors::FundraiserError;
+use crate::helpers::{DataLen, mul_div};
+use crate::state::{BPS_SCALER, MAX_BENEFICIARIES};
+use bytemuck::{Pod, Zeroable};
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::Pubkey;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct Beneficiary {
+    pub wallet: [u8; 32],
+    pub total: [u8; 8],
+}
+
+impl Beneficiary {
+    pub fn set_wallet(&mut self, wallet: &Pubkey) {
+        self.wallet.copy_from_slice(wallet.as_ref());
+    }
+
+    pub fn wallet(&self) -> Pubkey {
+        Pubkey::from(self.wallet)
+    }
+
+    pub fn set_total(&mut self, amount: u64) {
+        self.total = amount.to_le_bytes();
+    }
+
+    /// Amount donors allocated to the beneficiary.
+    pub fn total(&self) -> u64 {
+        u64::from_le_bytes(self.total)
+    }
+}
+
+/// Beneficiaries of a donor-directed fundraiser and what was allocated to each.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct AllocationsData {
+    pub count: [u8; 1],
+    pub bump: [u8; 1],
+    _padding: [u8; 6],
+    pub beneficiaries: [Beneficiary; MAX_BENEFICIARIES],
+}
+
+impl AllocationsData {
+    pub fn set_count(&mut self, count: u8) {
+        self.count = count.to_le_bytes();
+    }
+
+    pub fn count(&self) -> u8 {
+        u8::from_le_bytes(self.count)
+    }
+
+    pub fn set_bump(&mut self, bump: u8) {
+        self.bump = bump.to_le_bytes();
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+
+    /// Adds the amounts of a contribution split by `AllocationIxData::split`.
+    pub fn add(&mut self, amounts: &[u64; MAX_BENEFICIARIES]) -> Result<(), ProgramError> {
+        for (beneficiary, amount) in self.beneficiaries.iter_mut().zip(amounts.iter()) {
+            beneficiary.set_total(
+                beneficiary
+                    .total()
+                    .checked_add(*amount)
+                    .ok_or(ProgramError::ArithmeticOverflow)?,
+            );
+        }
+        Ok(())
+    }
+
+    /// Takes back the amounts of a refunded contribution.
+    pub fn remove(&mut self, amounts: &[u64; MAX_BENEFICIARIES]) {
+        for (beneficiary, amount) in self.beneficiaries.iter_mut().zip(amounts.iter()) {
+            beneficiary.set_total(beneficiary.total().saturating_sub(*amount));
+        }
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 6];
+    }
+}
+
+impl DataLen for AllocationsData {
+    const LEN: usize = core::mem::size_of::<AllocationsData>();
+}
+
+/// Trailing section of the initialize instruction data when `FLAG_ALLOCATION` is set.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct BeneficiariesIxData {
+    pub count: [u8; 1],
+    _padding: [u8; 7],
+    pub wallets: [[u8; 32]; MAX_BENEFICIARIES],
+}
+
+impl DataLen for BeneficiariesIxData {
+    const LEN: usize = core::mem::size_of::<BeneficiariesIxData>();
+}
+
+impl BeneficiariesIxData {
+    pub fn count(&self) -> u8 {
+        u8::from_le_bytes(self.count)
+    }
+
+    pub fn set_count(&mut self, count: u8) {
+        self.count = count.to_le_bytes();
+    }
+
+    pub fn set_wallet(&mut self, index: usize, wallet: &Pubkey) {
+        self.wallets[index].copy_from_slice(wallet.as_ref());
+    }
+
+    pub fn set_padding(&mut self) {
+        self._padding = [0; 7];
+    }
+
+    /// Every listed beneficiary needs a wallet. Unused entries are ignored.
+    pub fn validate(&self) -> Result<(), ProgramError> {
+        let count = self.count() as usize;
+        if count == 0 || count > MAX_BENEFICIARIES {
+            return Err(FundraiserError::InvalidAllocation.into());
+        }
+
+        if self.wallets[..count].iter().any(|w| *w == [0; 32]) {
+            return Err(FundraiserError::InvalidAllocation.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Trailing section of the contribute instruction data when `FLAG_ALLOCATION` is set,
+/// after the allowlist proof if any.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct AllocationIxData {
+    pub bps: [[u8; 2]; MAX_BENEFICIARIES],
+}
+
+impl DataLen for AllocationIxData {
+    const LEN: usize = core::mem::size_of::<AllocationIxData>();
+}
+
+impl AllocationIxData {
+    /// Part of the contribution, in basis points, going to the beneficiary at `index`.
+    pub fn bps(&self, index: usize) -> u16 {
+        u16::from_le_bytes(self.bps[index])
+    }
+
+    pub fn set_bps(&mut self, index: usize, bps: u16) {
+        self.bps[index] = bps.to_le_bytes();
+    }
+
+    /// Splits `amount` among the first `count` beneficiaries. The allocation must add
+    /// up to the whole amount, and the rounding dust goes to the last beneficiary
+    /// receiving anything.
+    pub fn split(&self, count: u8, amount: u64) -> Result<[u64; MAX_BENEFICIARIES], ProgramError> {
+        let count = count as usize;
+        let total: u64 = (0..MAX_BENEFICIARIES).map(|i| self.bps(i) as u64).sum();
+        if total != BPS_SCALER || (count..MAX_BENEFICIARIES).any(|i| self.bps(i) > 0) {
+            return Err(FundraiserError::InvalidAllocation.into());
+        }
+
+        let mut amounts = [0u64; MAX_BENEFICIARIES];
+        let mut last = 0;
+        for (index, split) in amounts[..count].iter_mut().enumerate() {
+            *split = mul_div(amount, self.bps(index) as u64, BPS_SCALER)?;
+            if self.bps(index) > 0 {
+                last = index;
+            }
+        }
+
+        let allocated: u64 = amounts.iter().sum();
+        amounts[last] = amounts[last]
+            .checked_add(amount - allocated)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(amounts)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::helpers::DataLen;
+use bytemuck::{Pod, Zeroable};
+use pinocchio::pubkey::Pubkey;
+
+/// Issuer's statement that a contributor passed its checks, valid until `expires_at`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct AttestationData {
+    pub issuer: [u8; 32],
+    pub contributor: [u8; 32],
+    pub expires_at: [u8; 8],
+    pub bump: [u8; 1],
+    _padding: [u8; 7],
+}
+
+impl AttestationData {
+    pub fn set_issuer(&mut self, issuer: &Pubkey) {
+        self.issuer.copy_from_slice(issuer.as_ref());
+    }
+
+    pub fn issuer(&self) -> Pubkey {
+        Pubkey::from(self.issuer)
+    }
+
+    pub fn set_contributor(&mut self, contributor: &Pubkey) {
+        self.contributor.copy_from_slice(contributor.as_ref());
+    }
+
+    pub fn contributor(&self) -> Pubkey {
+        Pubkey::from(self.contributor)
+    }
+
+    pub fn set_expires_at(&mut self, timestamp: u64) {
+        self.expires_at = timestamp.to_le_bytes();
+    }
+
+    pub fn expires_at(&self) -> u64 {
+        u64::from_le_bytes(self.expires_at)
+    }
+
+    pub fn set_bump(&mut self, bump: u8) {
+        self.bump = bump.to_le_bytes();
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 7];
+    }
+}
+
+impl DataLen for AttestationData {
+    const LEN: usize = core::mem::size_of::<AttestationData>();
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct IssueAttestationIxData {
+    pub expires_at: [u8; 8],
+}
+
+impl DataLen for IssueAttestationIxData {
+    const LEN: usize = core::mem::size_of::<IssueAttestationIxData>();
+}
+
+impl IssueAttestationIxData {
+    /// Unix timestamp the attestation is valid until. A past timestamp revokes it.
+    pub fn expires_at(&self) -> u64 {
+        u64::from_le_bytes(self.expires_at)
+    }
+
+    pub fn set_expires_at(&mut self, timestamp: u64) {
+        self.expires_at = timestamp.to_le_bytes();
+    }
+}
@@ -0,0 +1,37 @@
+use crate::helpers::DataLen;
+use bytemuck::{Pod, Zeroable};
+
+/// Marks a wallet the maker refuses contributions from. Its existence is the block.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct BlockRecordData {
+    pub blocked_at: [u8; 8],
+    pub bump: [u8; 1],
+    _padding: [u8; 7],
+}
+
+impl BlockRecordData {
+    pub fn set_blocked_at(&mut self, timestamp: u64) {
+        self.blocked_at = timestamp.to_le_bytes();
+    }
+
+    pub fn blocked_at(&self) -> u64 {
+        u64::from_le_bytes(self.blocked_at)
+    }
+
+    pub fn set_bump(&mut self, bump: u8) {
+        self.bump = bump.to_le_bytes();
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 7];
+    }
+}
+
+impl DataLen for BlockRecordData {
+    const LEN: usize = core::mem::size_of::<BlockRecordData>();
+}
@@ -0,0 +1,117 @@
+use crate::helpers::DataLen;
+use bytemuck::{Pod, Zeroable};
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::Pubkey;
+
+/// Umbrella campaign aggregating the progress of its child fundraisers, so the combined
+/// progress can be read from a single account. Amounts are summed as credited by each
+/// child, and a wallet backing several children counts once per child.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct CampaignData {
+    pub owner: [u8; 32],
+    pub total_target: [u8; 8],
+    pub total_raised: [u8; 8],
+    pub contributor_count: [u8; 4],
+    pub child_count: [u8; 2],
+    pub bump: [u8; 1],
+    _padding: [u8; 1],
+}
+
+impl CampaignData {
+    pub fn set_owner(&mut self, owner: &Pubkey) {
+        self.owner.copy_from_slice(owner.as_ref());
+    }
+
+    /// Wallet that approves the children linked to the campaign.
+    pub fn owner(&self) -> Pubkey {
+        Pubkey::from(self.owner)
+    }
+
+    pub fn set_total_target(&mut self, amount: u64) {
+        self.total_target = amount.to_le_bytes();
+    }
+
+    /// Sum of the targets of the children.
+    pub fn total_target(&self) -> u64 {
+        u64::from_le_bytes(self.total_target)
+    }
+
+    pub fn set_total_raised(&mut self, amount: u64) {
+        self.total_raised = amount.to_le_bytes();
+    }
+
+    /// Sum of the current amounts of the children.
+    pub fn total_raised(&self) -> u64 {
+        u64::from_le_bytes(self.total_raised)
+    }
+
+    pub fn set_contributor_count(&mut self, count: u32) {
+        self.contributor_count = count.to_le_bytes();
+    }
+
+    /// Sum of the number of contributors of the children.
+    pub fn contributor_count(&self) -> u32 {
+        u32::from_le_bytes(self.contributor_count)
+    }
+
+    pub fn set_child_count(&mut self, count: u16) {
+        self.child_count = count.to_le_bytes();
+    }
+
+    pub fn child_count(&self) -> u16 {
+        u16::from_le_bytes(self.child_count)
+    }
+
+    pub fn set_bump(&mut self, bump: u8) {
+        self.bump = bump.to_le_bytes();
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+
+    /// Links a child raising `target`.
+    pub fn add_child(&mut self, target: u64) -> Result<(), ProgramError> {
+        self.set_child_count(
+            self.child_count()
+                .checked_add(1)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+        self.set_total_target(
+            self.total_target()
+                .checked_add(target)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+        Ok(())
+    }
+
+    /// Records `added` raised by a child and `removed` taken back out of it, along with
+    /// a contributor that joined or left it.
+    pub fn record(
+        &mut self,
+        added: u64,
+        removed: u64,
+        joined: bool,
+        left: bool,
+    ) -> Result<(), ProgramError> {
+        self.set_total_raised(
+            self.total_raised()
+                .checked_add(added)
+                .ok_or(ProgramError::ArithmeticOverflow)?
+                .saturating_sub(removed),
+        );
+        self.set_contributor_count(
+            (self.contributor_count() + joined as u32).saturating_sub(left as u32),
+        );
+        Ok(())
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 1];
+    }
+}
+
+impl DataLen for CampaignData {
+    const LEN: usize = core::mem::size_of::<CampaignData>();
+}
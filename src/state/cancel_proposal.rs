@@ -0,0 +1,56 @@
+use crate::helpers::DataLen;
+use bytemuck::{Pod, Zeroable};
+use pinocchio::pubkey::Pubkey;
+
+/// Tally of contributor weight asking for the fundraiser to be cancelled.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct CancelProposalData {
+    pub proposer: [u8; 32],
+    pub votes: [u8; 8],
+    pub created_at: [u8; 8],
+    pub bump: [u8; 1],
+    _padding: [u8; 7],
+}
+
+impl CancelProposalData {
+    pub fn set_proposer(&mut self, proposer: &Pubkey) {
+        self.proposer.copy_from_slice(proposer.as_ref());
+    }
+
+    pub fn proposer(&self) -> Pubkey {
+        Pubkey::from(self.proposer)
+    }
+
+    pub fn set_votes(&mut self, weight: u64) {
+        self.votes = weight.to_le_bytes();
+    }
+
+    pub fn votes(&self) -> u64 {
+        u64::from_le_bytes(self.votes)
+    }
+
+    pub fn set_created_at(&mut self, timestamp: u64) {
+        self.created_at = timestamp.to_le_bytes();
+    }
+
+    pub fn created_at(&self) -> u64 {
+        u64::from_le_bytes(self.created_at)
+    }
+
+    pub fn set_bump(&mut self, bump: u8) {
+        self.bump = bump.to_le_bytes();
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 7];
+    }
+}
+
+impl DataLen for CancelProposalData {
+    const LEN: usize = core::mem::size_of::<CancelProposalData>();
+}
@@ -1,3 +1,5 @@
+use pinocchio::pubkey::Pubkey;
+
 pub const MIN_AMOUNT_TO_RAISE: u64 = 3;
 
 pub const FUNDRAISER_SEED: &[u8; 10] = b"fundraiser";
@@ -7,3 +9,124 @@ pub const SECONDS_PER_DAY: u64 = 86_400;
 pub const MAX_CONTRIBUTION_PERCENTAGE: u64 = 10;
 
 pub const PERCENTAGE_SCALER: u64 = 100;
+
+pub const MILESTONES_SEED: &[u8; 10] = b"milestones";
+
+pub const MAX_MILESTONES: usize = 8;
+
+/// Fundraiser flag: funds are released in tranches described by the milestones PDA.
+pub const FLAG_MILESTONES: u16 = 1 << 0;
+
+/// Fundraiser flag: raised funds vest linearly to the maker after the deadline.
+pub const FLAG_VESTING: u16 = 1 << 1;
+
+pub const VOTE_SEED: &[u8; 4] = b"vote";
+
+pub const CANCEL_PROPOSAL_SEED: &[u8; 6] = b"cancel";
+
+pub const CANCEL_VOTE_SEED: &[u8; 11] = b"cancel_vote";
+
+pub const VAULT_SEED: &[u8; 5] = b"vault";
+
+/// Fundraiser flag: the fundraiser raises native SOL held by a program owned vault PDA.
+pub const FLAG_NATIVE: u16 = 1 << 2;
+
+/// Mint of wrapped SOL, whose token accounts can be topped up with plain lamports.
+pub const NATIVE_MINT: Pubkey =
+    pinocchio_pubkey::pubkey!("So11111111111111111111111111111111111111112");
+
+pub const ACCEPTED_MINTS_SEED: &[u8; 14] = b"accepted_mints";
+
+pub const MAX_ACCEPTED_MINTS: usize = 4;
+
+/// Fundraiser flag: contributions are accepted in several mints, each converted into a
+/// normalized unit at a fixed rate.
+pub const FLAG_MULTI_MINT: u16 = 1 << 3;
+
+pub const BPS_SCALER: u64 = 10_000;
+
+/// Fundraiser flag: contributions are credited at their USD value, read from the price
+/// account stored on the fundraiser.
+pub const FLAG_ORACLE: u16 = 1 << 4;
+
+/// Decimals of the USD amounts credited by oracle priced fundraisers.
+pub const USD_DECIMALS: u8 = 6;
+
+/// Oldest price, in seconds, accepted from the price account.
+pub const MAX_PRICE_AGE: i64 = 60;
+
+/// Widest confidence interval accepted from the price account, relative to the price.
+pub const MAX_PRICE_CONFIDENCE_BPS: u64 = 200;
+
+/// Fundraiser flag: only contributors included in the fundraiser's Merkle root can contribute.
+pub const FLAG_ALLOWLIST: u16 = 1 << 5;
+
+/// Fundraiser flag: contributors must hold a minimum balance of the fundraiser's gate mint.
+pub const FLAG_TOKEN_GATE: u16 = 1 << 6;
+
+pub const ATTESTATION_SEED: &[u8; 11] = b"attestation";
+
+/// Fundraiser flag: contributors need an unexpired attestation from the fundraiser's issuer.
+pub const FLAG_ATTESTATION: u16 = 1 << 7;
+
+pub const BLOCK_SEED: &[u8; 5] = b"block";
+
+pub const RECEIPT_SEED: &[u8; 7] = b"receipt";
+
+/// Fundraiser flag: contributors receive receipt tokens 1:1 with their credited amount,
+/// minted by the fundraiser's receipt mint PDA and burned on refund.
+pub const FLAG_RECEIPT: u16 = 1 << 8;
+
+/// Fundraiser flag: contributors of a successful raise share the balance of a reward vault
+/// pro-rata to their contributions.
+pub const FLAG_REWARD: u16 = 1 << 9;
+
+/// Time after the deadline contributors have to claim their rewards before the maker can
+/// close the reward vault and take back what is left.
+pub const REWARD_CLAIM_PERIOD: u64 = 90 * SECONDS_PER_DAY;
+
+pub const TIERS_SEED: &[u8; 5] = b"tiers";
+
+pub const MAX_TIERS: usize = 8;
+
+/// Fundraiser flag: backers can pick one of the limited reward tiers of the tiers PDA.
+pub const FLAG_TIERS: u16 = 1 << 10;
+
+/// Fundraiser flag: early contributions earn extra shares of the rewards.
+pub const FLAG_EARLY_BIRD: u16 = 1 << 11;
+
+pub const MATCHING_SEED: &[u8; 8] = b"matching";
+
+/// Fundraiser flag: contributions are matched by a sponsor's matching pool PDA.
+pub const FLAG_MATCHING: u16 = 1 << 12;
+
+pub const ROUND_SEED: &[u8; 5] = b"round";
+
+pub const MAX_ROUND_PROJECTS: usize = 8;
+
+pub const CAMPAIGN_SEED: &[u8; 8] = b"campaign";
+
+/// Fundraiser flag: the fundraiser is a child of a campaign PDA that rolls up the
+/// progress of all its children.
+pub const FLAG_CAMPAIGN: u16 = 1 << 13;
+
+pub const ALLOCATIONS_SEED: &[u8; 11] = b"allocations";
+
+pub const MAX_BENEFICIARIES: usize = 8;
+
+/// Fundraiser flag: donors split their contributions among the beneficiaries of the
+/// allocations PDA, which are paid their totals instead of the maker.
+pub const FLAG_ALLOCATION: u16 = 1 << 14;
+
+/// Fundraiser flag: contributions are pledges backed by a token delegate approval to the
+/// fundraiser PDA, pulled into the vault by Collect once the raise succeeded.
+pub const FLAG_PLEDGE: u16 = 1 << 15;
+
+pub const SUBSCRIPTION_SEED: &[u8; 12] = b"subscription";
+
+pub const NONCE_SEED: &[u8; 5] = b"nonce";
+
+/// Native program verifying Ed25519 signatures, whose instructions are introspected to
+/// authenticate relayed contribution intents.
+pub const ED25519_PROGRAM_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("Ed25519SigVerify111111111111111111111111111");
@@ -1,12 +1,57 @@
-use crate::helpers::DataLen;
+use crate::helpers::{DataLen, mul_div};
+use crate::state::{MAX_ACCEPTED_MINTS, MAX_BENEFICIARIES};
 use bytemuck::{Pod, Zeroable};
 use pinocchio::pubkey::Pubkey;
 use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct MintDeposit {
+    pub raw: [u8; 8],
+    pub credited: [u8; 8],
+}
+
+impl MintDeposit {
+    pub fn set_raw(&mut self, amount: u64) {
+        self.raw = amount.to_le_bytes();
+    }
+
+    /// Amount of the mint transferred into its vault.
+    pub fn raw(&self) -> u64 {
+        u64::from_le_bytes(self.raw)
+    }
+
+    pub fn set_credited(&mut self, amount: u64) {
+        self.credited = amount.to_le_bytes();
+    }
+
+    /// Normalized amount the deposit was credited for.
+    pub fn credited(&self) -> u64 {
+        u64::from_le_bytes(self.credited)
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
 pub struct ContributorData {
     pub amount: [u8; 8],
+    /// Per-mint deposits of multi-mint fundraisers, indexed like the accepted mints.
+    pub deposits: [MintDeposit; MAX_ACCEPTED_MINTS],
+    /// Until when the position cannot be transferred because it has been voted with.
+    pub locked_until: [u8; 8],
+    pub shares: [u8; 8],
+    /// Matched by the matching pool on top of `amount`.
+    pub matched: [u8; 8],
+    pub reward_claimed: [u8; 1],
+    pub tier: [u8; 1],
+    pub collected: [u8; 1],
+    _padding: [u8; 5],
+    /// Amounts allocated to each beneficiary of a donor-directed fundraiser.
+    pub allocated: [[u8; 8]; MAX_BENEFICIARIES],
+    /// Token account approved for the pledge of a `FLAG_PLEDGE` fundraiser.
+    pub pledge_account: [u8; 32],
+    /// Part of the pledge that could not be collected.
+    pub shortfall: [u8; 8],
 }
 
 impl ContributorData {
@@ -17,6 +62,105 @@ impl ContributorData {
     pub fn amount(&self) -> u64 {
         u64::from_le_bytes(self.amount)
     }
+
+    pub fn set_locked_until(&mut self, timestamp: u64) {
+        self.locked_until = timestamp.to_le_bytes();
+    }
+
+    pub fn locked_until(&self) -> u64 {
+        u64::from_le_bytes(self.locked_until)
+    }
+
+    /// Keeps the position where it is at least until `timestamp`.
+    pub fn lock_until(&mut self, timestamp: u64) {
+        self.set_locked_until(self.locked_until().max(timestamp));
+    }
+
+    /// Cancellation votes lock the position for good, see `record_cancel_vote`.
+    pub fn voted_to_cancel(&self) -> bool {
+        self.locked_until() == u64::MAX
+    }
+
+    pub fn set_shares(&mut self, shares: u64) {
+        self.shares = shares.to_le_bytes();
+    }
+
+    /// Time-weighted weight of the contribution in reward distribution.
+    pub fn shares(&self) -> u64 {
+        u64::from_le_bytes(self.shares)
+    }
+
+    /// Shares that go with `amount` of the contributed amount.
+    pub fn shares_of(&self, amount: u64) -> Result<u64, ProgramError> {
+        if amount >= self.amount() {
+            return Ok(self.shares());
+        }
+        mul_div(self.shares(), amount, self.amount())
+    }
+
+    pub fn set_matched(&mut self, amount: u64) {
+        self.matched = amount.to_le_bytes();
+    }
+
+    pub fn matched(&self) -> u64 {
+        u64::from_le_bytes(self.matched)
+    }
+
+    pub fn set_reward_claimed(&mut self, claimed: bool) {
+        self.reward_claimed = [claimed as u8];
+    }
+
+    pub fn reward_claimed(&self) -> bool {
+        self.reward_claimed[0] != 0
+    }
+
+    pub fn set_tier(&mut self, tier: Option<u8>) {
+        self.tier = [tier.map_or(0, |index| index + 1)];
+    }
+
+    /// Index of the tier held by the contributor, if any.
+    pub fn tier(&self) -> Option<u8> {
+        self.tier[0].checked_sub(1)
+    }
+
+    pub fn set_allocated(&mut self, amounts: &[u64; MAX_BENEFICIARIES]) {
+        for (allocated, amount) in self.allocated.iter_mut().zip(amounts.iter()) {
+            *allocated = amount.to_le_bytes();
+        }
+    }
+
+    pub fn allocated(&self) -> [u64; MAX_BENEFICIARIES] {
+        self.allocated.map(u64::from_le_bytes)
+    }
+
+    pub fn set_collected(&mut self, collected: bool) {
+        self.collected = [collected as u8];
+    }
+
+    /// Whether the pledge has been collected, in full or not.
+    pub fn collected(&self) -> bool {
+        self.collected[0] != 0
+    }
+
+    pub fn set_pledge_account(&mut self, account: &Pubkey) {
+        self.pledge_account.copy_from_slice(account.as_ref());
+    }
+
+    pub fn pledge_account(&self) -> Pubkey {
+        Pubkey::from(self.pledge_account)
+    }
+
+    pub fn set_shortfall(&mut self, amount: u64) {
+        self.shortfall = amount.to_le_bytes();
+    }
+
+    pub fn shortfall(&self) -> u64 {
+        u64::from_le_bytes(self.shortfall)
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 5];
+    }
 }
 
 impl DataLen for ContributorData {
@@ -29,7 +173,9 @@ pub struct ContributeIxData {
     pub amount: [u8; 8],
     pub c_bump: [u8; 1],
     pub f_bump: [u8; 1],
-    _padding: [u8; 6],
+    pub wrap: [u8; 1],
+    pub tier: [u8; 1],
+    _padding: [u8; 4],
 }
 
 impl DataLen for ContributeIxData {
@@ -49,7 +195,82 @@ impl ContributeIxData {
         u8::from_le_bytes(self.f_bump)
     }
 
+    /// Contribute plain lamports that are wrapped into the wSOL vault.
+    pub fn wrap(&self) -> bool {
+        self.wrap[0] == 1
+    }
+
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = [wrap as u8];
+    }
+
+    /// Tier selected with the contribution, stored as its index plus one.
+    pub fn tier(&self) -> Option<u8> {
+        self.tier[0].checked_sub(1)
+    }
+
+    pub fn set_tier(&mut self, tier: Option<u8>) {
+        self.tier = [tier.map_or(0, |index| index + 1)];
+    }
+
     pub fn add_padding(&mut self) {
-        self._padding = [0; 6];
+        self._padding = [0; 4];
+    }
+}
+
+/// Trailing section of the contribute instruction data when `FLAG_ALLOWLIST` is set,
+/// followed by `depth` proof nodes of 32 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct AllowlistProofIxData {
+    pub cap: [u8; 8],
+    pub depth: [u8; 1],
+    _padding: [u8; 7],
+}
+
+impl DataLen for AllowlistProofIxData {
+    const LEN: usize = core::mem::size_of::<AllowlistProofIxData>();
+}
+
+impl AllowlistProofIxData {
+    /// Most the contributor may contribute in total. Zero means uncapped.
+    pub fn cap(&self) -> u64 {
+        u64::from_le_bytes(self.cap)
+    }
+
+    pub fn set_cap(&mut self, cap: u64) {
+        self.cap = cap.to_le_bytes();
+    }
+
+    pub fn depth(&self) -> u8 {
+        u8::from_le_bytes(self.depth)
+    }
+
+    pub fn set_depth(&mut self, depth: u8) {
+        self.depth = depth.to_le_bytes();
+    }
+
+    pub fn set_padding(&mut self) {
+        self._padding = [0; 7];
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct TransferPositionIxData {
+    pub amount: [u8; 8],
+}
+
+impl DataLen for TransferPositionIxData {
+    const LEN: usize = core::mem::size_of::<TransferPositionIxData>();
+}
+
+impl TransferPositionIxData {
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    pub fn set_amount(&mut self, amount: u64) {
+        self.amount = amount.to_le_bytes();
     }
 }
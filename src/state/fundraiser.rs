@@ -1,8 +1,35 @@
-use crate::helpers::DataLen;
+use crate::helpers::{DataLen, mul_div};
+use crate::state::{
+    BPS_SCALER, FLAG_ALLOCATION, FLAG_ALLOWLIST, FLAG_ATTESTATION, FLAG_CAMPAIGN, FLAG_MATCHING,
+    FLAG_MULTI_MINT, FLAG_NATIVE, FLAG_ORACLE, FLAG_PLEDGE, FLAG_RECEIPT, FLAG_TOKEN_GATE,
+    REWARD_CLAIM_PERIOD, SECONDS_PER_DAY,
+};
 use bytemuck::{Pod, Zeroable};
 use pinocchio::pubkey::Pubkey;
 use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
 
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FundraiserStatus {
+    Active = 0,
+    Cancelled = 1,
+    /// Funds went to the maker and the fundraiser only remains open to pay out rewards.
+    Succeeded = 2,
+}
+
+impl TryFrom<u8> for FundraiserStatus {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FundraiserStatus::Active),
+            1 => Ok(FundraiserStatus::Cancelled),
+            2 => Ok(FundraiserStatus::Succeeded),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
 pub struct FundraiserData {
@@ -11,9 +38,41 @@ pub struct FundraiserData {
     pub amount_to_raise: [u8; 8],
     pub current_amount: [u8; 8],
     pub time_started: [u8; 8],
+    pub released_amount: [u8; 8],
+    pub vesting_cliff: [u8; 8],
+    pub vesting_duration: [u8; 8],
+    pub price_account: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub gate_mint: [u8; 32],
+    pub gate_min_balance: [u8; 8],
+    pub issuer: [u8; 32],
+    pub blocked_count: [u8; 4],
+    pub reward_mint: [u8; 32],
+    pub reward_total: [u8; 8],
+    pub reward_basis: [u8; 8],
+    /// Shares whose reward has been paid out, out of `reward_basis`.
+    pub reward_claimed_shares: [u8; 8],
+    pub total_shares: [u8; 8],
+    pub bonus_period: [u8; 8],
+    pub bonus_bps: [u8; 2],
+    /// Part of `current_amount` paid in by the matching pool.
+    pub matched_amount: [u8; 8],
+    /// Quadratic funding round the fundraiser joined, if any.
+    pub round: [u8; 32],
+    /// Parent campaign of the fundraiser when `FLAG_CAMPAIGN` is set.
+    pub campaign: [u8; 32],
+    /// Pledges of a `FLAG_PLEDGE` fundraiser pulled into the vault by Collect.
+    pub collected_amount: [u8; 8],
+    /// Pledges that could not be pulled in full when they were collected.
+    pub shortfall_amount: [u8; 8],
+    /// Raised total a vesting schedule releases, taken at the first claim after the deadline.
+    pub vesting_base: [u8; 8],
     pub duration: [u8; 1],
     pub bump: [u8; 1],
-    _padding: [u8; 7],
+    pub status: [u8; 1],
+    pub flags: [u8; 2],
+    pub cancel_threshold: [u8; 1],
+    _padding: [u8; 2],
 }
 
 impl FundraiserData {
@@ -73,8 +132,342 @@ impl FundraiserData {
         u8::from_le_bytes(self.bump)
     }
 
+    pub fn set_released_amount(&mut self, amount: u64) {
+        self.released_amount = amount.to_le_bytes();
+    }
+
+    pub fn released_amount(&self) -> u64 {
+        u64::from_le_bytes(self.released_amount)
+    }
+
+    pub fn set_vesting_cliff(&mut self, seconds: u64) {
+        self.vesting_cliff = seconds.to_le_bytes();
+    }
+
+    pub fn vesting_cliff(&self) -> u64 {
+        u64::from_le_bytes(self.vesting_cliff)
+    }
+
+    pub fn set_vesting_duration(&mut self, seconds: u64) {
+        self.vesting_duration = seconds.to_le_bytes();
+    }
+
+    pub fn vesting_duration(&self) -> u64 {
+        u64::from_le_bytes(self.vesting_duration)
+    }
+
+    /// Amount of `vesting_base` the maker may have claimed by `now`. Vesting starts at the
+    /// deadline, nothing vests before the cliff and everything has vested once the
+    /// vesting duration has elapsed.
+    pub fn vested_amount(&self, now: u64) -> Result<u64, ProgramError> {
+        let start = self.deadline();
+        if now < start {
+            return Ok(0);
+        }
+
+        let elapsed = now - start;
+        if elapsed < self.vesting_cliff() {
+            return Ok(0);
+        }
+        if elapsed >= self.vesting_duration() {
+            return Ok(self.vesting_base());
+        }
+
+        mul_div(self.vesting_base(), elapsed, self.vesting_duration())
+    }
+
+    pub fn set_status(&mut self, status: FundraiserStatus) {
+        self.status = [status as u8];
+    }
+
+    pub fn status(&self) -> Result<FundraiserStatus, ProgramError> {
+        FundraiserStatus::try_from(self.status[0])
+    }
+
+    pub fn set_flags(&mut self, flags: u16) {
+        self.flags = flags.to_le_bytes();
+    }
+
+    pub fn flags(&self) -> u16 {
+        u16::from_le_bytes(self.flags)
+    }
+
+    pub fn has_flag(&self, flag: u16) -> bool {
+        self.flags() & flag == flag
+    }
+
+    pub fn set_cancel_threshold(&mut self, percentage: u8) {
+        self.cancel_threshold = percentage.to_le_bytes();
+    }
+
+    /// Percentage of the raised amount contributors need to hold to cancel the fundraiser.
+    /// Zero means contributors cannot cancel.
+    pub fn cancel_threshold(&self) -> u8 {
+        u8::from_le_bytes(self.cancel_threshold)
+    }
+
+    pub fn set_price_account(&mut self, price_account: &Pubkey) {
+        self.price_account.copy_from_slice(price_account.as_ref());
+    }
+
+    pub fn price_account(&self) -> Pubkey {
+        Pubkey::from(self.price_account)
+    }
+
+    pub fn set_merkle_root(&mut self, root: &[u8; 32]) {
+        self.merkle_root = *root;
+    }
+
+    /// Root of the allowlist tree when `FLAG_ALLOWLIST` is set.
+    pub fn merkle_root(&self) -> [u8; 32] {
+        self.merkle_root
+    }
+
+    pub fn set_gate_mint(&mut self, mint: &Pubkey) {
+        self.gate_mint.copy_from_slice(mint.as_ref());
+    }
+
+    pub fn gate_mint(&self) -> Pubkey {
+        Pubkey::from(self.gate_mint)
+    }
+
+    pub fn set_gate_min_balance(&mut self, amount: u64) {
+        self.gate_min_balance = amount.to_le_bytes();
+    }
+
+    /// Balance of the gate mint a contributor needs when `FLAG_TOKEN_GATE` is set.
+    pub fn gate_min_balance(&self) -> u64 {
+        u64::from_le_bytes(self.gate_min_balance)
+    }
+
+    pub fn set_issuer(&mut self, issuer: &Pubkey) {
+        self.issuer.copy_from_slice(issuer.as_ref());
+    }
+
+    /// Issuer whose attestations contributors need when `FLAG_ATTESTATION` is set.
+    pub fn issuer(&self) -> Pubkey {
+        Pubkey::from(self.issuer)
+    }
+
+    pub fn set_blocked_count(&mut self, count: u32) {
+        self.blocked_count = count.to_le_bytes();
+    }
+
+    /// Number of wallets currently blocked. Contributors only have to present their
+    /// block record PDA while it is non-zero.
+    pub fn blocked_count(&self) -> u32 {
+        u32::from_le_bytes(self.blocked_count)
+    }
+
+    pub fn set_reward_mint(&mut self, mint: &Pubkey) {
+        self.reward_mint.copy_from_slice(mint.as_ref());
+    }
+
+    /// Mint paid out to contributors when `FLAG_REWARD` is set.
+    pub fn reward_mint(&self) -> Pubkey {
+        Pubkey::from(self.reward_mint)
+    }
+
+    pub fn set_reward_total(&mut self, amount: u64) {
+        self.reward_total = amount.to_le_bytes();
+    }
+
+    /// Reward vault balance when the first reward was claimed, shared among contributors.
+    pub fn reward_total(&self) -> u64 {
+        u64::from_le_bytes(self.reward_total)
+    }
+
+    pub fn set_reward_basis(&mut self, amount: u64) {
+        self.reward_basis = amount.to_le_bytes();
+    }
+
+    /// `total_shares` when the first reward was claimed. Zero until then.
+    pub fn reward_basis(&self) -> u64 {
+        u64::from_le_bytes(self.reward_basis)
+    }
+
+    pub fn set_reward_claimed_shares(&mut self, shares: u64) {
+        self.reward_claimed_shares = shares.to_le_bytes();
+    }
+
+    pub fn reward_claimed_shares(&self) -> u64 {
+        u64::from_le_bytes(self.reward_claimed_shares)
+    }
+
+    /// Whether every contributor counted in `reward_basis` has claimed their reward.
+    pub fn rewards_claimed(&self) -> bool {
+        self.reward_basis() != 0 && self.reward_claimed_shares() >= self.reward_basis()
+    }
+
+    /// Whether the maker can close the rewards: every reward has been claimed, or the
+    /// claim period is over.
+    pub fn rewards_closable(&self, now: u64) -> bool {
+        self.rewards_claimed() || now >= self.deadline().saturating_add(REWARD_CLAIM_PERIOD)
+    }
+
+    pub fn set_total_shares(&mut self, shares: u64) {
+        self.total_shares = shares.to_le_bytes();
+    }
+
+    /// Sum of the contributors' shares, which weigh contributions for reward distribution.
+    pub fn total_shares(&self) -> u64 {
+        u64::from_le_bytes(self.total_shares)
+    }
+
+    pub fn set_bonus_period(&mut self, seconds: u64) {
+        self.bonus_period = seconds.to_le_bytes();
+    }
+
+    pub fn bonus_period(&self) -> u64 {
+        u64::from_le_bytes(self.bonus_period)
+    }
+
+    pub fn set_bonus_bps(&mut self, bps: u16) {
+        self.bonus_bps = bps.to_le_bytes();
+    }
+
+    /// Bonus on shares for contributions made at the very start of the fundraiser.
+    pub fn bonus_bps(&self) -> u16 {
+        u16::from_le_bytes(self.bonus_bps)
+    }
+
+    /// Shares earned by crediting `credited` at `now`. The early-bird bonus decays linearly
+    /// from `bonus_bps` at the start of the fundraiser to nothing after `bonus_period`.
+    pub fn shares_for(&self, credited: u64, now: u64) -> Result<u64, ProgramError> {
+        let elapsed = now.saturating_sub(self.time_started());
+        let period = self.bonus_period();
+        let bonus = if elapsed >= period {
+            0
+        } else {
+            mul_div(self.bonus_bps() as u64, period - elapsed, period)?
+        };
+
+        mul_div(credited, BPS_SCALER + bonus, BPS_SCALER)
+    }
+
+    /// Whether the raise was successful: funds have been paid to the maker, or the
+    /// deadline passed with the target met.
+    pub fn has_succeeded(&self, now: u64) -> Result<bool, ProgramError> {
+        Ok(match self.status()? {
+            FundraiserStatus::Succeeded => true,
+            FundraiserStatus::Active => self.is_target_met() && now >= self.deadline(),
+            FundraiserStatus::Cancelled => false,
+        })
+    }
+
+    /// Whether contributors can take their money back: the fundraiser was cancelled, or
+    /// its deadline passed without the target being met.
+    pub fn is_refundable(&self, now: u64) -> Result<bool, ProgramError> {
+        Ok(match self.status()? {
+            FundraiserStatus::Cancelled => true,
+            FundraiserStatus::Active => !self.is_target_met() && now >= self.deadline(),
+            FundraiserStatus::Succeeded => false,
+        })
+    }
+
+    pub fn set_matched_amount(&mut self, amount: u64) {
+        self.matched_amount = amount.to_le_bytes();
+    }
+
+    pub fn matched_amount(&self) -> u64 {
+        u64::from_le_bytes(self.matched_amount)
+    }
+
+    pub fn set_round(&mut self, round: &Pubkey) {
+        self.round.copy_from_slice(round.as_ref());
+    }
+
+    pub fn round(&self) -> Pubkey {
+        Pubkey::from(self.round)
+    }
+
+    pub fn in_round(&self) -> bool {
+        self.round != [0; 32]
+    }
+
+    pub fn set_campaign(&mut self, campaign: &Pubkey) {
+        self.campaign.copy_from_slice(campaign.as_ref());
+    }
+
+    pub fn campaign(&self) -> Pubkey {
+        Pubkey::from(self.campaign)
+    }
+
+    pub fn set_collected_amount(&mut self, amount: u64) {
+        self.collected_amount = amount.to_le_bytes();
+    }
+
+    pub fn collected_amount(&self) -> u64 {
+        u64::from_le_bytes(self.collected_amount)
+    }
+
+    pub fn set_shortfall_amount(&mut self, amount: u64) {
+        self.shortfall_amount = amount.to_le_bytes();
+    }
+
+    pub fn shortfall_amount(&self) -> u64 {
+        u64::from_le_bytes(self.shortfall_amount)
+    }
+
+    pub fn set_vesting_base(&mut self, amount: u64) {
+        self.vesting_base = amount.to_le_bytes();
+    }
+
+    pub fn vesting_base(&self) -> u64 {
+        u64::from_le_bytes(self.vesting_base)
+    }
+
+    /// Whether every pledge has been collected, in full or not.
+    pub fn pledges_collected(&self) -> Result<bool, ProgramError> {
+        Ok(self
+            .collected_amount()
+            .checked_add(self.shortfall_amount())
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            >= self.current_amount())
+    }
+
+    /// Amount put in by the contributors themselves, which is what they vote with.
+    pub fn contributed_amount(&self) -> u64 {
+        self.current_amount().saturating_sub(self.matched_amount())
+    }
+
+    /// Whether `current_amount` counts a normalized unit rather than raw vault tokens.
+    pub fn is_normalized(&self) -> bool {
+        self.has_flag(FLAG_MULTI_MINT) || self.has_flag(FLAG_ORACLE)
+    }
+
+    /// Whether a plain token transfer into the vault is all a contribution needs to be
+    /// credited: nothing has to be proven, matched, split or minted with it. Recurring
+    /// and relayed contributions are limited to such fundraisers.
+    pub fn takes_plain_transfers(&self) -> bool {
+        ![
+            FLAG_NATIVE,
+            FLAG_MULTI_MINT,
+            FLAG_ORACLE,
+            FLAG_ALLOWLIST,
+            FLAG_TOKEN_GATE,
+            FLAG_ATTESTATION,
+            FLAG_RECEIPT,
+            FLAG_MATCHING,
+            FLAG_CAMPAIGN,
+            FLAG_ALLOCATION,
+            FLAG_PLEDGE,
+        ]
+        .iter()
+        .any(|flag| self.has_flag(*flag))
+    }
+
+    /// Unix timestamp after which the fundraiser no longer accepts contributions.
+    pub fn deadline(&self) -> u64 {
+        self.time_started() + self.duration() as u64 * SECONDS_PER_DAY
+    }
+
+    pub fn is_target_met(&self) -> bool {
+        self.current_amount() >= self.amount_to_raise()
+    }
+
     pub fn add_padding(&mut self) {
-        self._padding = [0; 7];
+        self._padding = [0; 2];
     }
 }
 
@@ -88,7 +481,9 @@ pub struct InitializeFundraiserIxData {
     pub amount_to_raise: [u8; 8],
     pub duration: [u8; 1],
     pub bump: [u8; 1],
-    _padding: [u8; 6],
+    pub flags: [u8; 2],
+    pub cancel_threshold: [u8; 1],
+    _padding: [u8; 3],
 }
 
 impl DataLen for InitializeFundraiserIxData {
@@ -112,7 +507,202 @@ impl InitializeFundraiserIxData {
         self.duration = amount.to_le_bytes();
     }
 
+    pub fn flags(&self) -> u16 {
+        u16::from_le_bytes(self.flags)
+    }
+
+    pub fn set_flags(&mut self, flags: u16) {
+        self.flags = flags.to_le_bytes();
+    }
+
+    pub fn has_flag(&self, flag: u16) -> bool {
+        self.flags() & flag == flag
+    }
+
+    pub fn cancel_threshold(&self) -> u8 {
+        u8::from_le_bytes(self.cancel_threshold)
+    }
+
+    pub fn set_cancel_threshold(&mut self, percentage: u8) {
+        self.cancel_threshold = percentage.to_le_bytes();
+    }
+
+    pub fn set_padding(&mut self) {
+        self._padding = [0u8; 3];
+    }
+}
+
+/// Trailing section of the initialize instruction data when `FLAG_VESTING` is set.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct VestingIxData {
+    pub cliff: [u8; 8],
+    pub duration: [u8; 8],
+}
+
+impl DataLen for VestingIxData {
+    const LEN: usize = core::mem::size_of::<VestingIxData>();
+}
+
+impl VestingIxData {
+    pub fn cliff(&self) -> u64 {
+        u64::from_le_bytes(self.cliff)
+    }
+
+    pub fn set_cliff(&mut self, seconds: u64) {
+        self.cliff = seconds.to_le_bytes();
+    }
+
+    pub fn duration(&self) -> u64 {
+        u64::from_le_bytes(self.duration)
+    }
+
+    pub fn set_duration(&mut self, seconds: u64) {
+        self.duration = seconds.to_le_bytes();
+    }
+}
+
+/// Optional instruction data of Refund and Check. Empty data keeps the payout wrapped.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct UnwrapIxData {
+    pub unwrap: [u8; 1],
+    _padding: [u8; 7],
+}
+
+impl DataLen for UnwrapIxData {
+    const LEN: usize = core::mem::size_of::<UnwrapIxData>();
+}
+
+impl UnwrapIxData {
+    /// Close the receiving wSOL account after the payout so it arrives as SOL.
+    pub fn unwrap(&self) -> bool {
+        self.unwrap[0] == 1
+    }
+
+    pub fn set_unwrap(&mut self, unwrap: bool) {
+        self.unwrap = [unwrap as u8];
+    }
+
+    pub fn set_padding(&mut self) {
+        self._padding = [0; 7];
+    }
+}
+
+/// Trailing section of the initialize instruction data when `FLAG_ORACLE` is set.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct OracleIxData {
+    pub price_account: [u8; 32],
+}
+
+impl DataLen for OracleIxData {
+    const LEN: usize = core::mem::size_of::<OracleIxData>();
+}
+
+impl OracleIxData {
+    pub fn price_account(&self) -> Pubkey {
+        Pubkey::from(self.price_account)
+    }
+
+    pub fn set_price_account(&mut self, price_account: &Pubkey) {
+        self.price_account.copy_from_slice(price_account.as_ref());
+    }
+}
+
+/// Trailing section of the initialize instruction data when `FLAG_ALLOWLIST` is set.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct AllowlistIxData {
+    pub merkle_root: [u8; 32],
+}
+
+impl DataLen for AllowlistIxData {
+    const LEN: usize = core::mem::size_of::<AllowlistIxData>();
+}
+
+/// Trailing section of the initialize instruction data when `FLAG_TOKEN_GATE` is set.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct TokenGateIxData {
+    pub gate_mint: [u8; 32],
+    pub min_balance: [u8; 8],
+}
+
+impl DataLen for TokenGateIxData {
+    const LEN: usize = core::mem::size_of::<TokenGateIxData>();
+}
+
+impl TokenGateIxData {
+    pub fn gate_mint(&self) -> Pubkey {
+        Pubkey::from(self.gate_mint)
+    }
+
+    pub fn set_gate_mint(&mut self, mint: &Pubkey) {
+        self.gate_mint.copy_from_slice(mint.as_ref());
+    }
+
+    pub fn min_balance(&self) -> u64 {
+        u64::from_le_bytes(self.min_balance)
+    }
+
+    pub fn set_min_balance(&mut self, amount: u64) {
+        self.min_balance = amount.to_le_bytes();
+    }
+}
+
+/// Trailing section of the initialize instruction data when `FLAG_ATTESTATION` is set.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct AttestationIxData {
+    pub issuer: [u8; 32],
+}
+
+impl DataLen for AttestationIxData {
+    const LEN: usize = core::mem::size_of::<AttestationIxData>();
+}
+
+impl AttestationIxData {
+    pub fn issuer(&self) -> Pubkey {
+        Pubkey::from(self.issuer)
+    }
+
+    pub fn set_issuer(&mut self, issuer: &Pubkey) {
+        self.issuer.copy_from_slice(issuer.as_ref());
+    }
+}
+
+/// Trailing section of the initialize instruction data when `FLAG_EARLY_BIRD` is set.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct EarlyBirdIxData {
+    pub bonus_period: [u8; 8],
+    pub bonus_bps: [u8; 2],
+    _padding: [u8; 6],
+}
+
+impl DataLen for EarlyBirdIxData {
+    const LEN: usize = core::mem::size_of::<EarlyBirdIxData>();
+}
+
+impl EarlyBirdIxData {
+    pub fn bonus_period(&self) -> u64 {
+        u64::from_le_bytes(self.bonus_period)
+    }
+
+    pub fn set_bonus_period(&mut self, seconds: u64) {
+        self.bonus_period = seconds.to_le_bytes();
+    }
+
+    pub fn bonus_bps(&self) -> u16 {
+        u16::from_le_bytes(self.bonus_bps)
+    }
+
+    pub fn set_bonus_bps(&mut self, bps: u16) {
+        self.bonus_bps = bps.to_le_bytes();
+    }
+
     pub fn set_padding(&mut self) {
-        self._padding = [0u8; 6];
+        self._padding = [0; 6];
     }
 }
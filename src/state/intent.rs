@@ -0,0 +1,75 @@
+use crate::helpers::DataLen;
+use bytemuck::{Pod, Zeroable};
+use pinocchio::pubkey::Pubkey;
+
+/// Contribution signed off-chain by a contributor and submitted by a relayer. These
+/// bytes are both the instruction data and the message signed with Ed25519.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct ContributionIntent {
+    pub fundraiser: [u8; 32],
+    pub amount: [u8; 8],
+    pub nonce: [u8; 8],
+    pub expiry: [u8; 8],
+}
+
+impl DataLen for ContributionIntent {
+    const LEN: usize = core::mem::size_of::<ContributionIntent>();
+}
+
+impl ContributionIntent {
+    pub fn fundraiser(&self) -> Pubkey {
+        Pubkey::from(self.fundraiser)
+    }
+
+    pub fn set_fundraiser(&mut self, fundraiser: &Pubkey) {
+        self.fundraiser.copy_from_slice(fundraiser.as_ref());
+    }
+
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    pub fn set_amount(&mut self, amount: u64) {
+        self.amount = amount.to_le_bytes();
+    }
+
+    /// Chosen by the contributor, each nonce can be used once.
+    pub fn nonce(&self) -> u64 {
+        u64::from_le_bytes(self.nonce)
+    }
+
+    pub fn set_nonce(&mut self, nonce: u64) {
+        self.nonce = nonce.to_le_bytes();
+    }
+
+    /// Unix timestamp after which the intent can no longer be submitted.
+    pub fn expiry(&self) -> u64 {
+        u64::from_le_bytes(self.expiry)
+    }
+
+    pub fn set_expiry(&mut self, expiry: u64) {
+        self.expiry = expiry.to_le_bytes();
+    }
+}
+
+/// Marks a nonce of a contributor as used. Its existence is all that matters.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct NonceData {
+    pub bump: [u8; 1],
+}
+
+impl NonceData {
+    pub fn set_bump(&mut self, bump: u8) {
+        self.bump = bump.to_le_bytes();
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+}
+
+impl DataLen for NonceData {
+    const LEN: usize = core::mem::size_of::<NonceData>();
+}
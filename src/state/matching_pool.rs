@@ -0,0 +1,121 @@
+use crate::helpers::{DataLen, mul_div};
+use crate::state::BPS_SCALER;
+use bytemuck::{Pod, Zeroable};
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::Pubkey;
+
+/// Sponsor's pool matching contributions to a fundraiser. Its funds are held by the
+/// token account of the fundraiser's mint owned by the pool PDA.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct MatchingPoolData {
+    pub sponsor: [u8; 32],
+    pub donor_cap: [u8; 8],
+    pub ratio_bps: [u8; 2],
+    pub bump: [u8; 1],
+    _padding: [u8; 5],
+}
+
+impl MatchingPoolData {
+    pub fn set_sponsor(&mut self, sponsor: &Pubkey) {
+        self.sponsor.copy_from_slice(sponsor.as_ref());
+    }
+
+    pub fn sponsor(&self) -> Pubkey {
+        Pubkey::from(self.sponsor)
+    }
+
+    pub fn set_donor_cap(&mut self, amount: u64) {
+        self.donor_cap = amount.to_le_bytes();
+    }
+
+    /// Most matched for a single contributor. Zero means uncapped.
+    pub fn donor_cap(&self) -> u64 {
+        u64::from_le_bytes(self.donor_cap)
+    }
+
+    pub fn set_ratio_bps(&mut self, bps: u16) {
+        self.ratio_bps = bps.to_le_bytes();
+    }
+
+    /// Amount matched per contributed amount, 10_000 being a 1:1 match.
+    pub fn ratio_bps(&self) -> u16 {
+        u16::from_le_bytes(self.ratio_bps)
+    }
+
+    pub fn set_bump(&mut self, bump: u8) {
+        self.bump = bump.to_le_bytes();
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+
+    /// Match owed for contributing `amount` by a contributor already matched `matched`,
+    /// limited by the donor cap and by what is left in the pool.
+    pub fn match_for(
+        &self,
+        amount: u64,
+        matched: u64,
+        available: u64,
+    ) -> Result<u64, ProgramError> {
+        let mut owed = mul_div(amount, self.ratio_bps() as u64, BPS_SCALER)?;
+        if self.donor_cap() > 0 {
+            owed = owed.min(self.donor_cap().saturating_sub(matched));
+        }
+        Ok(owed.min(available))
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 5];
+    }
+}
+
+impl DataLen for MatchingPoolData {
+    const LEN: usize = core::mem::size_of::<MatchingPoolData>();
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct FundMatchingPoolIxData {
+    pub amount: [u8; 8],
+    pub donor_cap: [u8; 8],
+    pub ratio_bps: [u8; 2],
+    _padding: [u8; 6],
+}
+
+impl DataLen for FundMatchingPoolIxData {
+    const LEN: usize = core::mem::size_of::<FundMatchingPoolIxData>();
+}
+
+impl FundMatchingPoolIxData {
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    pub fn set_amount(&mut self, amount: u64) {
+        self.amount = amount.to_le_bytes();
+    }
+
+    /// Only read when the pool is created, top-ups keep the pool's terms.
+    pub fn donor_cap(&self) -> u64 {
+        u64::from_le_bytes(self.donor_cap)
+    }
+
+    pub fn set_donor_cap(&mut self, amount: u64) {
+        self.donor_cap = amount.to_le_bytes();
+    }
+
+    /// Only read when the pool is created, top-ups keep the pool's terms.
+    pub fn ratio_bps(&self) -> u16 {
+        u16::from_le_bytes(self.ratio_bps)
+    }
+
+    pub fn set_ratio_bps(&mut self, bps: u16) {
+        self.ratio_bps = bps.to_le_bytes();
+    }
+
+    pub fn set_padding(&mut self) {
+        self._padding = [0; 6];
+    }
+}
@@ -0,0 +1,252 @@
+use crate::errors::FundraiserError;
+use crate::helpers::DataLen;
+use crate::state::{MAX_MILESTONES, PERCENTAGE_SCALER};
+use bytemuck::{Pod, Zeroable};
+use pinocchio::program_error::ProgramError;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct Tranche {
+    pub unlock_time: [u8; 8],
+    pub votes_for: [u8; 8],
+    pub votes_against: [u8; 8],
+    pub percentage: [u8; 1],
+    pub attempt: [u8; 1],
+    _padding: [u8; 6],
+}
+
+impl Tranche {
+    pub fn set_unlock_time(&mut self, timestamp: u64) {
+        self.unlock_time = timestamp.to_le_bytes();
+    }
+
+    pub fn unlock_time(&self) -> u64 {
+        u64::from_le_bytes(self.unlock_time)
+    }
+
+    pub fn set_votes_for(&mut self, weight: u64) {
+        self.votes_for = weight.to_le_bytes();
+    }
+
+    pub fn votes_for(&self) -> u64 {
+        u64::from_le_bytes(self.votes_for)
+    }
+
+    pub fn set_votes_against(&mut self, weight: u64) {
+        self.votes_against = weight.to_le_bytes();
+    }
+
+    pub fn votes_against(&self) -> u64 {
+        u64::from_le_bytes(self.votes_against)
+    }
+
+    pub fn set_percentage(&mut self, percentage: u8) {
+        self.percentage = percentage.to_le_bytes();
+    }
+
+    pub fn percentage(&self) -> u8 {
+        u8::from_le_bytes(self.percentage)
+    }
+
+    pub fn set_attempt(&mut self, attempt: u8) {
+        self.attempt = attempt.to_le_bytes();
+    }
+
+    /// Number of times the vote on the tranche was re-opened after being rejected.
+    pub fn attempt(&self) -> u8 {
+        u8::from_le_bytes(self.attempt)
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 6];
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct MilestonesData {
+    pub count: [u8; 1],
+    pub next_tranche: [u8; 1],
+    pub bump: [u8; 1],
+    pub quorum: [u8; 1],
+    pub threshold: [u8; 1],
+    _padding: [u8; 3],
+    pub voting_period: [u8; 8],
+    pub tranches: [Tranche; MAX_MILESTONES],
+}
+
+impl MilestonesData {
+    pub fn set_count(&mut self, count: u8) {
+        self.count = count.to_le_bytes();
+    }
+
+    pub fn count(&self) -> u8 {
+        u8::from_le_bytes(self.count)
+    }
+
+    pub fn set_next_tranche(&mut self, index: u8) {
+        self.next_tranche = index.to_le_bytes();
+    }
+
+    pub fn next_tranche(&self) -> u8 {
+        u8::from_le_bytes(self.next_tranche)
+    }
+
+    pub fn set_bump(&mut self, bump: u8) {
+        self.bump = bump.to_le_bytes();
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+
+    pub fn set_quorum(&mut self, percentage: u8) {
+        self.quorum = percentage.to_le_bytes();
+    }
+
+    /// Percentage of the raised amount that has to take part in a tranche vote.
+    pub fn quorum(&self) -> u8 {
+        u8::from_le_bytes(self.quorum)
+    }
+
+    pub fn set_threshold(&mut self, percentage: u8) {
+        self.threshold = percentage.to_le_bytes();
+    }
+
+    /// Percentage of the cast votes that has to approve a tranche.
+    pub fn threshold(&self) -> u8 {
+        u8::from_le_bytes(self.threshold)
+    }
+
+    pub fn set_voting_period(&mut self, seconds: u64) {
+        self.voting_period = seconds.to_le_bytes();
+    }
+
+    pub fn voting_period(&self) -> u64 {
+        u64::from_le_bytes(self.voting_period)
+    }
+
+    /// Tranche releases only need contributor approval when a quorum was configured.
+    pub fn requires_vote(&self) -> bool {
+        self.quorum() > 0
+    }
+
+    /// Votes on a tranche are accepted from its unlock time until the voting period ends.
+    pub fn voting_window(&self, index: u8) -> (u64, u64) {
+        let start = self.tranches[index as usize].unlock_time();
+        (start, start.saturating_add(self.voting_period()))
+    }
+
+    pub fn vote_passed(&self, index: u8, raised: u64) -> bool {
+        let tranche = &self.tranches[index as usize];
+        let votes_for = tranche.votes_for() as u128;
+        let votes_cast = votes_for + tranche.votes_against() as u128;
+
+        votes_cast * PERCENTAGE_SCALER as u128 >= self.quorum() as u128 * raised as u128
+            && votes_for * PERCENTAGE_SCALER as u128 >= self.threshold() as u128 * votes_cast
+            && votes_for > 0
+    }
+
+    /// Sum of the percentages of every tranche up to and including `index`.
+    pub fn cumulative_percentage(&self, index: u8) -> u64 {
+        self.tranches[..=index as usize]
+            .iter()
+            .map(|t| t.percentage() as u64)
+            .sum()
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 3];
+    }
+}
+
+impl DataLen for MilestonesData {
+    const LEN: usize = core::mem::size_of::<MilestonesData>();
+}
+
+/// Trailing section of the initialize instruction data when `FLAG_MILESTONES` is set.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct MilestonesIxData {
+    pub count: [u8; 1],
+    pub quorum: [u8; 1],
+    pub threshold: [u8; 1],
+    _padding: [u8; 5],
+    pub voting_period: [u8; 8],
+    pub tranches: [Tranche; MAX_MILESTONES],
+}
+
+impl DataLen for MilestonesIxData {
+    const LEN: usize = core::mem::size_of::<MilestonesIxData>();
+}
+
+impl MilestonesIxData {
+    pub fn count(&self) -> u8 {
+        u8::from_le_bytes(self.count)
+    }
+
+    pub fn set_count(&mut self, count: u8) {
+        self.count = count.to_le_bytes();
+    }
+
+    pub fn quorum(&self) -> u8 {
+        u8::from_le_bytes(self.quorum)
+    }
+
+    pub fn set_quorum(&mut self, percentage: u8) {
+        self.quorum = percentage.to_le_bytes();
+    }
+
+    pub fn threshold(&self) -> u8 {
+        u8::from_le_bytes(self.threshold)
+    }
+
+    pub fn set_threshold(&mut self, percentage: u8) {
+        self.threshold = percentage.to_le_bytes();
+    }
+
+    pub fn voting_period(&self) -> u64 {
+        u64::from_le_bytes(self.voting_period)
+    }
+
+    pub fn set_voting_period(&mut self, seconds: u64) {
+        self.voting_period = seconds.to_le_bytes();
+    }
+
+    pub fn set_padding(&mut self) {
+        self._padding = [0; 5];
+    }
+
+    /// Tranches must unlock in chronological order and their percentages must add up
+    /// to the whole raise. A quorum of zero disables contributor voting.
+    pub fn validate(&self) -> Result<(), ProgramError> {
+        let count = self.count() as usize;
+        if count == 0 || count > MAX_MILESTONES {
+            return Err(FundraiserError::InvalidMilestones.into());
+        }
+
+        let tranches = &self.tranches[..count];
+        let total: u64 = tranches.iter().map(|t| t.percentage() as u64).sum();
+        if total != PERCENTAGE_SCALER {
+            return Err(FundraiserError::InvalidMilestones.into());
+        }
+
+        if tranches.iter().any(|t| t.percentage() == 0)
+            || tranches
+                .windows(2)
+                .any(|w| w[0].unlock_time() > w[1].unlock_time())
+        {
+            return Err(FundraiserError::InvalidMilestones.into());
+        }
+
+        if self.quorum() as u64 > PERCENTAGE_SCALER || self.threshold() as u64 > PERCENTAGE_SCALER {
+            return Err(FundraiserError::InvalidMilestones.into());
+        }
+
+        if self.quorum() > 0 && (self.threshold() == 0 || self.voting_period() == 0) {
+            return Err(FundraiserError::InvalidMilestones.into());
+        }
+
+        Ok(())
+    }
+}
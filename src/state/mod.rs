@@ -1,7 +1,33 @@
+mod accepted_mints;
+mod allocations;
+mod attestation;
+mod block_record;
+mod campaign;
+mod cancel_proposal;
 mod constants;
 mod contibutor;
 mod fundraiser;
+mod intent;
+mod matching_pool;
+mod milestones;
+mod round;
+mod subscription;
+mod tiers;
+mod vote;
 
+pub use accepted_mints::*;
+pub use allocations::*;
+pub use attestation::*;
+pub use block_record::*;
+pub use campaign::*;
+pub use cancel_proposal::*;
 pub use constants::*;
 pub use contibutor::*;
 pub use fundraiser::*;
+pub use intent::*;
+pub use matching_pool::*;
+pub use milestones::*;
+pub use round::*;
+pub use subscription::*;
+pub use tiers::*;
+pub use vote::*;
@@ -0,0 +1,216 @@
+use crate::helpers::DataLen;
+use crate::state::MAX_ROUND_PROJECTS;
+use bytemuck::{Pod, Zeroable};
+use pinocchio::pubkey::Pubkey;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RoundProject {
+    pub fundraiser: [u8; 32],
+    pub maker: [u8; 32],
+    pub sqrt_sum: [u8; 8],
+    pub match_amount: [u8; 8],
+    pub paid: [u8; 1],
+    _padding: [u8; 7],
+}
+
+impl RoundProject {
+    pub fn set_fundraiser(&mut self, fundraiser: &Pubkey) {
+        self.fundraiser.copy_from_slice(fundraiser.as_ref());
+    }
+
+    pub fn fundraiser(&self) -> Pubkey {
+        Pubkey::from(self.fundraiser)
+    }
+
+    pub fn set_maker(&mut self, maker: &Pubkey) {
+        self.maker.copy_from_slice(maker.as_ref());
+    }
+
+    /// Wallet the project's match is paid to.
+    pub fn maker(&self) -> Pubkey {
+        Pubkey::from(self.maker)
+    }
+
+    pub fn set_sqrt_sum(&mut self, sum: u64) {
+        self.sqrt_sum = sum.to_le_bytes();
+    }
+
+    /// Sum of the square roots of each contributor's total to the fundraiser.
+    pub fn sqrt_sum(&self) -> u64 {
+        u64::from_le_bytes(self.sqrt_sum)
+    }
+
+    /// Quadratic funding weight of the project, the square of `sqrt_sum`.
+    pub fn weight(&self) -> u128 {
+        self.sqrt_sum() as u128 * self.sqrt_sum() as u128
+    }
+
+    pub fn set_match_amount(&mut self, amount: u64) {
+        self.match_amount = amount.to_le_bytes();
+    }
+
+    /// Part of the round pool owed to the project, set when the round is finalized.
+    pub fn match_amount(&self) -> u64 {
+        u64::from_le_bytes(self.match_amount)
+    }
+
+    pub fn set_paid(&mut self, paid: bool) {
+        self.paid = [paid as u8];
+    }
+
+    pub fn paid(&self) -> bool {
+        self.paid[0] != 0
+    }
+}
+
+/// Quadratic funding round splitting the pool held by its vault among the fundraisers
+/// that joined it.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RoundData {
+    pub admin: [u8; 32],
+    pub mint: [u8; 32],
+    pub ends_at: [u8; 8],
+    pub pool_amount: [u8; 8],
+    pub id: [u8; 8],
+    pub project_count: [u8; 1],
+    pub finalized: [u8; 1],
+    pub bump: [u8; 1],
+    _padding: [u8; 5],
+    pub projects: [RoundProject; MAX_ROUND_PROJECTS],
+}
+
+impl RoundData {
+    pub fn set_admin(&mut self, admin: &Pubkey) {
+        self.admin.copy_from_slice(admin.as_ref());
+    }
+
+    pub fn admin(&self) -> Pubkey {
+        Pubkey::from(self.admin)
+    }
+
+    pub fn set_mint(&mut self, mint: &Pubkey) {
+        self.mint.copy_from_slice(mint.as_ref());
+    }
+
+    pub fn mint(&self) -> Pubkey {
+        Pubkey::from(self.mint)
+    }
+
+    pub fn set_ends_at(&mut self, timestamp: u64) {
+        self.ends_at = timestamp.to_le_bytes();
+    }
+
+    /// Unix timestamp from which the round can be finalized.
+    pub fn ends_at(&self) -> u64 {
+        u64::from_le_bytes(self.ends_at)
+    }
+
+    pub fn set_pool_amount(&mut self, amount: u64) {
+        self.pool_amount = amount.to_le_bytes();
+    }
+
+    /// Balance of the round vault when the round was finalized.
+    pub fn pool_amount(&self) -> u64 {
+        u64::from_le_bytes(self.pool_amount)
+    }
+
+    pub fn set_id(&mut self, id: u64) {
+        self.id = id.to_le_bytes();
+    }
+
+    /// Id picked by the admin, so one admin can run several rounds.
+    pub fn id(&self) -> u64 {
+        u64::from_le_bytes(self.id)
+    }
+
+    pub fn set_project_count(&mut self, count: u8) {
+        self.project_count = count.to_le_bytes();
+    }
+
+    pub fn project_count(&self) -> u8 {
+        u8::from_le_bytes(self.project_count)
+    }
+
+    pub fn set_finalized(&mut self, finalized: bool) {
+        self.finalized = [finalized as u8];
+    }
+
+    pub fn finalized(&self) -> bool {
+        self.finalized[0] != 0
+    }
+
+    pub fn set_bump(&mut self, bump: u8) {
+        self.bump = bump.to_le_bytes();
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+
+    /// Index of `fundraiser` among the projects of the round.
+    pub fn find(&self, fundraiser: &Pubkey) -> Option<usize> {
+        self.projects[..self.project_count() as usize]
+            .iter()
+            .position(|project| project.fundraiser().eq(fundraiser))
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 5];
+    }
+}
+
+impl DataLen for RoundData {
+    const LEN: usize = core::mem::size_of::<RoundData>();
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct CreateRoundIxData {
+    pub ends_at: [u8; 8],
+    pub id: [u8; 8],
+}
+
+impl DataLen for CreateRoundIxData {
+    const LEN: usize = core::mem::size_of::<CreateRoundIxData>();
+}
+
+impl CreateRoundIxData {
+    pub fn ends_at(&self) -> u64 {
+        u64::from_le_bytes(self.ends_at)
+    }
+
+    pub fn set_ends_at(&mut self, timestamp: u64) {
+        self.ends_at = timestamp.to_le_bytes();
+    }
+
+    pub fn id(&self) -> u64 {
+        u64::from_le_bytes(self.id)
+    }
+
+    pub fn set_id(&mut self, id: u64) {
+        self.id = id.to_le_bytes();
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct DistributeRoundIxData {
+    pub project: [u8; 1],
+}
+
+impl DataLen for DistributeRoundIxData {
+    const LEN: usize = core::mem::size_of::<DistributeRoundIxData>();
+}
+
+impl DistributeRoundIxData {
+    /// Index of the project paid among the projects of the round.
+    pub fn project(&self) -> u8 {
+        u8::from_le_bytes(self.project)
+    }
+
+    pub fn set_project(&mut self, index: u8) {
+        self.project = index.to_le_bytes();
+    }
+}
@@ -0,0 +1,152 @@
+use crate::helpers::DataLen;
+use bytemuck::{Pod, Zeroable};
+use pinocchio::pubkey::Pubkey;
+
+/// Recurring contribution of a contributor to a fundraiser. Each period, anyone can pull
+/// `amount` from the approved token account into the vault once it is due, and is paid
+/// the bounty out of the lamports the contributor deposited with the subscription.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SubscriptionData {
+    pub contributor: [u8; 32],
+    pub source: [u8; 32],
+    pub amount: [u8; 8],
+    pub interval: [u8; 8],
+    pub next_due: [u8; 8],
+    pub bounty: [u8; 8],
+    pub remaining: [u8; 4],
+    pub bump: [u8; 1],
+    _padding: [u8; 3],
+}
+
+impl SubscriptionData {
+    pub fn set_contributor(&mut self, contributor: &Pubkey) {
+        self.contributor.copy_from_slice(contributor.as_ref());
+    }
+
+    pub fn contributor(&self) -> Pubkey {
+        Pubkey::from(self.contributor)
+    }
+
+    pub fn set_source(&mut self, source: &Pubkey) {
+        self.source.copy_from_slice(source.as_ref());
+    }
+
+    /// Token account of the contributor approving the subscription PDA as delegate.
+    pub fn source(&self) -> Pubkey {
+        Pubkey::from(self.source)
+    }
+
+    pub fn set_amount(&mut self, amount: u64) {
+        self.amount = amount.to_le_bytes();
+    }
+
+    /// Amount contributed each period.
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    pub fn set_interval(&mut self, interval: u64) {
+        self.interval = interval.to_le_bytes();
+    }
+
+    /// Seconds between two payments.
+    pub fn interval(&self) -> u64 {
+        u64::from_le_bytes(self.interval)
+    }
+
+    pub fn set_next_due(&mut self, timestamp: u64) {
+        self.next_due = timestamp.to_le_bytes();
+    }
+
+    /// Unix timestamp from which the next payment can be processed.
+    pub fn next_due(&self) -> u64 {
+        u64::from_le_bytes(self.next_due)
+    }
+
+    pub fn set_bounty(&mut self, lamports: u64) {
+        self.bounty = lamports.to_le_bytes();
+    }
+
+    /// Lamports paid to whoever processes a payment.
+    pub fn bounty(&self) -> u64 {
+        u64::from_le_bytes(self.bounty)
+    }
+
+    pub fn set_remaining(&mut self, periods: u32) {
+        self.remaining = periods.to_le_bytes();
+    }
+
+    /// Payments left before the subscription ends.
+    pub fn remaining(&self) -> u32 {
+        u32::from_le_bytes(self.remaining)
+    }
+
+    pub fn set_bump(&mut self, bump: u8) {
+        self.bump = bump.to_le_bytes();
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 3];
+    }
+}
+
+impl DataLen for SubscriptionData {
+    const LEN: usize = core::mem::size_of::<SubscriptionData>();
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SubscriptionIxData {
+    pub amount: [u8; 8],
+    pub interval: [u8; 8],
+    pub bounty: [u8; 8],
+    pub periods: [u8; 4],
+    _padding: [u8; 4],
+}
+
+impl DataLen for SubscriptionIxData {
+    const LEN: usize = core::mem::size_of::<SubscriptionIxData>();
+}
+
+impl SubscriptionIxData {
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    pub fn set_amount(&mut self, amount: u64) {
+        self.amount = amount.to_le_bytes();
+    }
+
+    pub fn interval(&self) -> u64 {
+        u64::from_le_bytes(self.interval)
+    }
+
+    pub fn set_interval(&mut self, interval: u64) {
+        self.interval = interval.to_le_bytes();
+    }
+
+    pub fn bounty(&self) -> u64 {
+        u64::from_le_bytes(self.bounty)
+    }
+
+    pub fn set_bounty(&mut self, lamports: u64) {
+        self.bounty = lamports.to_le_bytes();
+    }
+
+    pub fn periods(&self) -> u32 {
+        u32::from_le_bytes(self.periods)
+    }
+
+    pub fn set_periods(&mut self, periods: u32) {
+        self.periods = periods.to_le_bytes();
+    }
+
+    pub fn set_padding(&mut self) {
+        self._padding = [0; 4];
+    }
+}
@@ -0,0 +1,147 @@
+use crate::errors::FundraiserError;
+use crate::helpers::DataLen;
+use crate::state::MAX_TIERS;
+use bytemuck::{Pod, Zeroable};
+use pinocchio::program_error::ProgramError;
+
+/// A perk backers can pick when contributing, limited to `supply` backers.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct Tier {
+    pub price: [u8; 8],
+    pub supply: [u8; 4],
+    pub claimed: [u8; 4],
+}
+
+impl Tier {
+    pub fn set_price(&mut self, amount: u64) {
+        self.price = amount.to_le_bytes();
+    }
+
+    /// Least a backer must have contributed, in credited units, to hold the tier.
+    pub fn price(&self) -> u64 {
+        u64::from_le_bytes(self.price)
+    }
+
+    pub fn set_supply(&mut self, supply: u32) {
+        self.supply = supply.to_le_bytes();
+    }
+
+    pub fn supply(&self) -> u32 {
+        u32::from_le_bytes(self.supply)
+    }
+
+    pub fn set_claimed(&mut self, claimed: u32) {
+        self.claimed = claimed.to_le_bytes();
+    }
+
+    pub fn claimed(&self) -> u32 {
+        u32::from_le_bytes(self.claimed)
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct TiersData {
+    pub count: [u8; 1],
+    pub bump: [u8; 1],
+    _padding: [u8; 6],
+    pub tiers: [Tier; MAX_TIERS],
+}
+
+impl TiersData {
+    pub fn set_count(&mut self, count: u8) {
+        self.count = count.to_le_bytes();
+    }
+
+    pub fn count(&self) -> u8 {
+        u8::from_le_bytes(self.count)
+    }
+
+    pub fn set_bump(&mut self, bump: u8) {
+        self.bump = bump.to_le_bytes();
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+
+    /// Takes a slot of the tier at `index` for a backer who contributed `contributed`.
+    pub fn claim(&mut self, index: u8, contributed: u64) -> Result<(), ProgramError> {
+        if index >= self.count() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let tier = &mut self.tiers[index as usize];
+        if contributed < tier.price() {
+            return Err(FundraiserError::BelowTierPrice.into());
+        }
+        if tier.claimed() >= tier.supply() {
+            return Err(FundraiserError::TierSoldOut.into());
+        }
+        tier.set_claimed(tier.claimed() + 1);
+
+        Ok(())
+    }
+
+    /// Gives a slot of the tier at `index` back, when its backer is refunded.
+    pub fn release(&mut self, index: u8) -> Result<(), ProgramError> {
+        if index >= self.count() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let tier = &mut self.tiers[index as usize];
+        tier.set_claimed(tier.claimed().saturating_sub(1));
+
+        Ok(())
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 6];
+    }
+}
+
+impl DataLen for TiersData {
+    const LEN: usize = core::mem::size_of::<TiersData>();
+}
+
+/// Trailing section of the initialize instruction data when `FLAG_TIERS` is set.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct TiersIxData {
+    pub count: [u8; 1],
+    _padding: [u8; 7],
+    pub tiers: [Tier; MAX_TIERS],
+}
+
+impl DataLen for TiersIxData {
+    const LEN: usize = core::mem::size_of::<TiersIxData>();
+}
+
+impl TiersIxData {
+    pub fn count(&self) -> u8 {
+        u8::from_le_bytes(self.count)
+    }
+
+    pub fn set_count(&mut self, count: u8) {
+        self.count = count.to_le_bytes();
+    }
+
+    pub fn set_padding(&mut self) {
+        self._padding = [0; 7];
+    }
+
+    /// Every configured tier needs a supply. Unused entries are ignored.
+    pub fn validate(&self) -> Result<(), ProgramError> {
+        let count = self.count() as usize;
+        if count == 0 || count > MAX_TIERS {
+            return Err(FundraiserError::InvalidTiers.into());
+        }
+
+        if self.tiers[..count].iter().any(|t| t.supply() == 0) {
+            return Err(FundraiserError::InvalidTiers.into());
+        }
+
+        Ok(())
+    }
+}
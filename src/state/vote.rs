@@ -0,0 +1,96 @@
+use crate::helpers::DataLen;
+use bytemuck::{Pod, Zeroable};
+
+/// Records a contributor's vote on a single tranche or on a cancellation proposal.
+/// Its existence prevents double voting, until a rejected tranche vote is re-opened.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct VoteRecordData {
+    pub weight: [u8; 8],
+    pub tranche: [u8; 1],
+    pub approve: [u8; 1],
+    pub attempt: [u8; 1],
+    _padding: [u8; 5],
+}
+
+impl VoteRecordData {
+    pub fn set_weight(&mut self, weight: u64) {
+        self.weight = weight.to_le_bytes();
+    }
+
+    pub fn weight(&self) -> u64 {
+        u64::from_le_bytes(self.weight)
+    }
+
+    pub fn set_tranche(&mut self, index: u8) {
+        self.tranche = index.to_le_bytes();
+    }
+
+    pub fn tranche(&self) -> u8 {
+        u8::from_le_bytes(self.tranche)
+    }
+
+    pub fn set_approve(&mut self, approve: bool) {
+        self.approve = [approve as u8];
+    }
+
+    pub fn approve(&self) -> bool {
+        self.approve[0] != 0
+    }
+
+    pub fn set_attempt(&mut self, attempt: u8) {
+        self.attempt = attempt.to_le_bytes();
+    }
+
+    /// Attempt of the tranche vote the record was cast in.
+    pub fn attempt(&self) -> u8 {
+        u8::from_le_bytes(self.attempt)
+    }
+
+    pub fn add_padding(&mut self) {
+        self._padding = [0; 5];
+    }
+}
+
+impl DataLen for VoteRecordData {
+    const LEN: usize = core::mem::size_of::<VoteRecordData>();
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct CastVoteIxData {
+    pub tranche: [u8; 1],
+    pub approve: [u8; 1],
+    pub bump: [u8; 1],
+    _padding: [u8; 5],
+}
+
+impl DataLen for CastVoteIxData {
+    const LEN: usize = core::mem::size_of::<CastVoteIxData>();
+}
+
+impl CastVoteIxData {
+    pub fn tranche(&self) -> u8 {
+        u8::from_le_bytes(self.tranche)
+    }
+
+    pub fn set_tranche(&mut self, index: u8) {
+        self.tranche = index.to_le_bytes();
+    }
+
+    pub fn approve(&self) -> bool {
+        self.approve[0] != 0
+    }
+
+    pub fn set_approve(&mut self, approve: bool) {
+        self.approve = [approve as u8];
+    }
+
+    pub fn bump(&self) -> u8 {
+        u8::from_le_bytes(self.bump)
+    }
+
+    pub fn set_padding(&mut self) {
+        self._padding = [0; 5];
+    }
+}
@@ -0,0 +1,30 @@
+use crate::state::FundraiserData;
+
+fn fundraiser(bonus_period: u64, bonus_bps: u16) -> FundraiserData {
+    let mut fundraiser = FundraiserData::default();
+    fundraiser.set_time_started(1_000);
+    fundraiser.set_bonus_period(bonus_period);
+    fundraiser.set_bonus_bps(bonus_bps);
+    fundraiser
+}
+
+#[test]
+fn bonus_decays_linearly_to_nothing() {
+    let fundraiser = fundraiser(86_400, 2_000);
+
+    assert_eq!(fundraiser.shares_for(1_000, 1_000).unwrap(), 1_200);
+    assert_eq!(fundraiser.shares_for(1_000, 1_000 + 43_200).unwrap(), 1_100);
+    assert_eq!(fundraiser.shares_for(1_000, 1_000 + 86_400).unwrap(), 1_000);
+    assert_eq!(
+        fundraiser.shares_for(1_000, 1_000 + 500_000).unwrap(),
+        1_000
+    );
+}
+
+#[test]
+fn shares_match_amounts_without_bonus() {
+    let fundraiser = fundraiser(0, 0);
+
+    assert_eq!(fundraiser.shares_for(123_456, 1_000).unwrap(), 123_456);
+    assert_eq!(fundraiser.shares_for(u64::MAX, 2_000).unwrap(), u64::MAX);
+}
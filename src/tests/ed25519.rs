@@ -0,0 +1,59 @@
+use crate::helpers::check_ed25519_data;
+
+const SIGNER: [u8; 32] = [7; 32];
+
+/// Ed25519 program instruction data verifying one signature over `message`, with the
+/// public key, signature and message all carried by the instruction itself.
+fn ed25519_data(signer: &[u8; 32], message: &[u8]) -> Vec<u8> {
+    let public_key_offset: u16 = 16;
+    let signature_offset: u16 = public_key_offset + 32;
+    let message_offset: u16 = signature_offset + 64;
+
+    let mut data = vec![1u8, 0];
+    for value in [
+        signature_offset,
+        u16::MAX,
+        public_key_offset,
+        u16::MAX,
+        message_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(signer);
+    data.extend_from_slice(&[0; 64]);
+    data.extend_from_slice(message);
+    data
+}
+
+#[test]
+fn accepts_the_signed_message() {
+    let data = ed25519_data(&SIGNER, b"intent");
+    assert!(check_ed25519_data(&data, &SIGNER, b"intent").is_ok());
+}
+
+#[test]
+fn rejects_another_signer_or_message() {
+    let data = ed25519_data(&SIGNER, b"intent");
+    assert!(check_ed25519_data(&data, &[8; 32], b"intent").is_err());
+    assert!(check_ed25519_data(&data, &SIGNER, b"intent!").is_err());
+    assert!(check_ed25519_data(&data, &SIGNER, b"inten").is_err());
+}
+
+#[test]
+fn rejects_data_pointing_elsewhere() {
+    // the public key is read from another instruction of the transaction
+    let mut data = ed25519_data(&SIGNER, b"intent");
+    data[8..10].copy_from_slice(&0u16.to_le_bytes());
+    assert!(check_ed25519_data(&data, &SIGNER, b"intent").is_err());
+
+    // several signatures
+    let mut data = ed25519_data(&SIGNER, b"intent");
+    data[0] = 2;
+    assert!(check_ed25519_data(&data, &SIGNER, b"intent").is_err());
+
+    // offsets past the end of the data
+    let data = ed25519_data(&SIGNER, b"intent");
+    assert!(check_ed25519_data(&data[..100], &SIGNER, b"intent").is_err());
+}
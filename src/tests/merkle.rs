@@ -0,0 +1,88 @@
+use crate::helpers::{allowlist_leaf, merkle_parent, verify_merkle_proof};
+use pinocchio::pubkey::Pubkey;
+
+/// Allowlist tree built off-chain, matching the hashing done by the program.
+pub struct MerkleTree {
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Builds the tree over `(contributor, cap)` entries. An odd node is carried up
+    /// to the next layer unchanged.
+    pub fn new(entries: &[(Pubkey, u64)]) -> Self {
+        let leaves: Vec<[u8; 32]> = entries
+            .iter()
+            .map(|(contributor, cap)| allowlist_leaf(contributor, *cap))
+            .collect();
+        assert!(!leaves.is_empty());
+
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => merkle_parent(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+
+        Self { layers }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().unwrap()[0]
+    }
+
+    /// Sibling nodes from the leaf at `index` up to the root.
+    pub fn proof(&self, mut index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+fn entries(count: u8) -> Vec<(Pubkey, u64)> {
+    (0..count).map(|i| ([i; 32], i as u64 * 1_000)).collect()
+}
+
+#[test]
+fn every_entry_proves_inclusion() {
+    for count in 1..=17 {
+        let entries = entries(count);
+        let tree = MerkleTree::new(&entries);
+
+        for (index, (contributor, cap)) in entries.iter().enumerate() {
+            let leaf = allowlist_leaf(contributor, *cap);
+            assert!(verify_merkle_proof(leaf, &tree.proof(index), &tree.root()));
+        }
+    }
+}
+
+#[test]
+fn proof_does_not_verify_a_different_cap_or_contributor() {
+    let entries = entries(9);
+    let tree = MerkleTree::new(&entries);
+    let proof = tree.proof(3);
+    let (contributor, cap) = entries[3];
+
+    assert!(!verify_merkle_proof(
+        allowlist_leaf(&contributor, cap + 1),
+        &proof,
+        &tree.root()
+    ));
+    assert!(!verify_merkle_proof(
+        allowlist_leaf(&[42; 32], cap),
+        &proof,
+        &tree.root()
+    ));
+}
@@ -1,3 +1,14 @@
+#[cfg(test)]
+mod early_bird;
+#[cfg(test)]
+mod ed25519;
+#[cfg(test)]
+mod merkle;
+#[cfg(test)]
+mod pro_rata;
+#[cfg(test)]
+mod quadratic;
+
 #[cfg(test)]
 mod tests {
 
@@ -8,7 +19,7 @@ mod tests {
         CreateAssociatedTokenAccount, CreateMint, MintTo,
         spl_token::{
             self,
-            solana_program::{msg, rent::Rent, sysvar::SysvarId},
+            solana_program::{clock::Clock, msg, rent::Rent, sysvar::SysvarId},
         },
     };
 
@@ -549,6 +560,11 @@ mod tests {
         assert!(ok1);
 
         //------------------------------------------------------------------------------------
+        // the target was missed, so the contribution is refundable once the deadline passed
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 86_400 * duration as i64;
+        svm.set_sysvar::<Clock>(&clock);
+
         let refund_ix_discriminator: u8 = 2;
         let refund_ix = Instruction {
             program_id: program_id,
@@ -596,4 +612,3353 @@ mod tests {
 
         assert!(ok2);
     }
+
+    fn send_ix(svm: &mut LiteSVM, ix: Instruction, payer: &Keypair, signers: &[&Keypair]) -> bool {
+        let message = Message::new(&[ix], Some(&payer.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        let transaction = Transaction::new(signers, message, recent_blockhash);
+
+        match svm.send_transaction(transaction) {
+            Ok(tx) => {
+                msg!("CUs Consumed: {}", tx.compute_units_consumed);
+                msg!("Tx Logs: {:?}", tx.logs);
+                true
+            }
+            Err(err) => {
+                msg!("transaction failed with {:?}", err);
+                false
+            }
+        }
+    }
+
+    fn fundraiser_accounts(maker: &Pubkey, mint: &Pubkey) -> (Pubkey, u8, Pubkey) {
+        let (fundraiser, f_bump) =
+            Pubkey::find_program_address(&[b"fundraiser".as_ref(), maker.as_ref()], &program_id());
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser, mint);
+        (fundraiser, f_bump, vault)
+    }
+
+    fn initialize_ix(
+        maker: &Pubkey,
+        mint: &Pubkey,
+        data: Vec<u8>,
+        extra_accounts: Vec<AccountMeta>,
+    ) -> Instruction {
+        let (fundraiser, _, vault) = fundraiser_accounts(maker, mint);
+        let mut accounts = vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(
+                ASSOCIATED_TOKEN_PROGRAM_ID.parse::<Pubkey>().unwrap(),
+                false,
+            ),
+            AccountMeta::new_readonly(Rent::id(), false),
+        ];
+        accounts.extend(extra_accounts);
+
+        Instruction {
+            program_id: program_id(),
+            accounts,
+            data: [vec![0u8], data].concat(),
+        }
+    }
+
+    fn initialize_data(
+        amount_to_raise: u64,
+        duration: u8,
+        flags: u16,
+    ) -> crate::state::InitializeFundraiserIxData {
+        let mut ix_data = crate::state::InitializeFundraiserIxData::default();
+        ix_data.set_amount_to_raise(amount_to_raise);
+        ix_data.set_duration(duration);
+        ix_data.set_flags(flags);
+        ix_data.set_padding();
+        ix_data
+    }
+
+    /// Creates a funded contributor with an ATA holding `balance` tokens of `mint`.
+    fn new_contributor(
+        svm: &mut LiteSVM,
+        payer: &Keypair,
+        mint: &Pubkey,
+        balance: u64,
+    ) -> (Keypair, Pubkey) {
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 2 * LAMPORTS_PER_SOL)
+            .expect("Airdrop failed");
+
+        let contributor_ata = CreateAssociatedTokenAccount::new(svm, &contributor, mint)
+            .owner(&contributor.pubkey())
+            .token_program_id(&TOKEN_PROGRAM_ID)
+            .send()
+            .unwrap();
+
+        MintTo::new(svm, payer, mint, &contributor_ata, balance)
+            .owner(payer)
+            .send()
+            .unwrap();
+
+        (contributor, contributor_ata)
+    }
+
+    fn contributor_pda(fundraiser: &Pubkey, contributor: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"contributor".as_ref(),
+                fundraiser.as_ref(),
+                contributor.as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    fn contribute_ix(
+        maker: &Pubkey,
+        mint: &Pubkey,
+        contributor: &Pubkey,
+        contributor_ata: &Pubkey,
+        amount: u64,
+        extra_accounts: Vec<AccountMeta>,
+    ) -> Instruction {
+        let (fundraiser, f_bump, vault) = fundraiser_accounts(maker, mint);
+        let (contributor_account, c_bump) = contributor_pda(&fundraiser, contributor);
+        let mut accounts = vec![
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(contributor_account, false),
+            AccountMeta::new(*contributor_ata, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(solana_sdk_ids::system_program::ID, false),
+            AccountMeta::new(
+                ASSOCIATED_TOKEN_PROGRAM_ID.parse::<Pubkey>().unwrap(),
+                false,
+            ),
+            AccountMeta::new(Rent::id(), false),
+        ];
+        accounts.extend(extra_accounts);
+
+        let data = [
+            vec![1u8],
+            amount.to_le_bytes().to_vec(),
+            c_bump.to_le_bytes().to_vec(),
+            f_bump.to_le_bytes().to_vec(),
+            vec![0; 6],
+        ]
+        .concat();
+
+        Instruction {
+            program_id: program_id(),
+            accounts,
+            data,
+        }
+    }
+
+    fn refund_ix(
+        maker: &Pubkey,
+        mint: &Pubkey,
+        contributor: &Pubkey,
+        contributor_ata: &Pubkey,
+    ) -> Instruction {
+        let (fundraiser, _, vault) = fundraiser_accounts(maker, mint);
+        let (contributor_account, _) = contributor_pda(&fundraiser, contributor);
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*contributor, true),
+                AccountMeta::new(*maker, false),
+                AccountMeta::new(*mint, false),
+                AccountMeta::new(fundraiser, false),
+                AccountMeta::new(contributor_account, false),
+                AccountMeta::new(*contributor_ata, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new(solana_sdk_ids::system_program::ID, false),
+                AccountMeta::new(
+                    ASSOCIATED_TOKEN_PROGRAM_ID.parse::<Pubkey>().unwrap(),
+                    false,
+                ),
+                AccountMeta::new(Rent::id(), false),
+            ],
+            data: vec![2u8],
+        }
+    }
+
+    fn cancel_ix(maker: &Pubkey, mint: &Pubkey) -> Instruction {
+        let (fundraiser, _, _) = fundraiser_accounts(maker, mint);
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*maker, true),
+                AccountMeta::new(fundraiser, false),
+            ],
+            data: vec![5u8],
+        }
+    }
+
+    fn advance_clock(svm: &mut LiteSVM, seconds: i64) {
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += seconds;
+        svm.set_sysvar::<Clock>(&clock);
+    }
+
+    fn token_balance(svm: &LiteSVM, token_account: &Pubkey) -> u64 {
+        let account = svm.get_account(token_account).unwrap();
+        u64::from_le_bytes(account.data[64..72].try_into().unwrap())
+    }
+
+    fn milestones_data(
+        tranches: &[(u8, u64)],
+        quorum: u8,
+        threshold: u8,
+        voting_period: u64,
+    ) -> Vec<u8> {
+        let mut milestones = crate::state::MilestonesIxData::default();
+        milestones.set_count(tranches.len() as u8);
+        milestones.set_quorum(quorum);
+        milestones.set_threshold(threshold);
+        milestones.set_voting_period(voting_period);
+        milestones.set_padding();
+        for (tranche, (percentage, unlock_time)) in milestones.tranches.iter_mut().zip(tranches) {
+            tranche.set_percentage(*percentage);
+            tranche.set_unlock_time(*unlock_time);
+        }
+        bytemuck::bytes_of(&milestones).to_vec()
+    }
+
+    #[test]
+    pub fn test_claim_tranche_instruction() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let (fundraiser, _, vault) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let milestones = Pubkey::find_program_address(
+            &[b"milestones".as_ref(), fundraiser.as_ref()],
+            &program_id(),
+        )
+        .0;
+
+        let amount_to_raise: u64 = 1_000_000;
+        let data = [
+            bytemuck::bytes_of(&initialize_data(
+                amount_to_raise,
+                7,
+                crate::state::FLAG_MILESTONES,
+            ))
+            .to_vec(),
+            // 40% is already unlocked, the remaining 60% unlocks far in the future
+            milestones_data(&[(40, 0), (60, u64::MAX)], 0, 0, 0),
+        ]
+        .concat();
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            data,
+            vec![AccountMeta::new(milestones, false)],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (contributor, contributor_ata) =
+            new_contributor(&mut svm, &payer, &mint, amount_to_raise);
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+            amount_to_raise,
+            vec![],
+        );
+        assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let claim_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(fundraiser, false),
+                AccountMeta::new(milestones, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(maker_ata, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+            data: vec![4u8],
+        };
+
+        assert!(send_ix(&mut svm, claim_ix.clone(), &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_ata), 400_000);
+
+        // the second tranche is still locked
+        svm.expire_blockhash();
+        assert!(!send_ix(&mut svm, claim_ix, &payer, &[&payer]));
+
+        // the raise succeeded, so contributors cannot pull their share out of it
+        let refund = refund_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+        );
+        assert!(!send_ix(
+            &mut svm,
+            refund.clone(),
+            &contributor,
+            &[&contributor]
+        ));
+
+        // cancelling mid-way makes the remaining 60% refundable
+        let cancel = cancel_ix(&payer.pubkey(), &mint);
+        assert!(send_ix(&mut svm, cancel, &payer, &[&payer]));
+
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, refund, &contributor, &[&contributor]));
+        assert_eq!(token_balance(&svm, &contributor_ata), 600_000);
+    }
+
+    fn cast_vote_ix(
+        fundraiser: &Pubkey,
+        milestones: &Pubkey,
+        contributor: &Pubkey,
+        tranche: u8,
+        approve: bool,
+    ) -> Instruction {
+        let (contributor_account, _) = contributor_pda(fundraiser, contributor);
+        let (vote_record, v_bump) = Pubkey::find_program_address(
+            &[
+                b"vote".as_ref(),
+                fundraiser.as_ref(),
+                contributor.as_ref(),
+                &[tranche],
+            ],
+            &program_id(),
+        );
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*contributor, true),
+                AccountMeta::new_readonly(*fundraiser, false),
+                AccountMeta::new(*milestones, false),
+                AccountMeta::new(contributor_account, false),
+                AccountMeta::new(vote_record, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+            ],
+            data: vec![6u8, tranche, approve as u8, v_bump, 0, 0, 0, 0, 0],
+        }
+    }
+
+    #[test]
+    pub fn test_cast_vote_instruction() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let (fundraiser, _, vault) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let milestones = Pubkey::find_program_address(
+            &[b"milestones".as_ref(), fundraiser.as_ref()],
+            &program_id(),
+        )
+        .0;
+
+        let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+        let voting_period: u64 = 3_600;
+        let amount_to_raise: u64 = 1_000_000;
+        let data = [
+            bytemuck::bytes_of(&initialize_data(
+                amount_to_raise,
+                7,
+                crate::state::FLAG_MILESTONES,
+            ))
+            .to_vec(),
+            // half of the raise has to vote and 60% of the votes have to approve
+            milestones_data(&[(100, now)], 50, 60, voting_period),
+        ]
+        .concat();
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            data,
+            vec![AccountMeta::new(milestones, false)],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (backer, backer_ata) = new_contributor(&mut svm, &payer, &mint, 600_000);
+        let (critic, critic_ata) = new_contributor(&mut svm, &payer, &mint, 400_000);
+        for (contributor, contributor_ata, amount) in [
+            (&backer, backer_ata, 600_000),
+            (&critic, critic_ata, 400_000),
+        ] {
+            let contribute = contribute_ix(
+                &payer.pubkey(),
+                &mint,
+                &contributor.pubkey(),
+                &contributor_ata,
+                amount,
+                vec![],
+            );
+            assert!(send_ix(&mut svm, contribute, contributor, &[contributor]));
+        }
+
+        let approve = cast_vote_ix(&fundraiser, &milestones, &backer.pubkey(), 0, true);
+        assert!(send_ix(&mut svm, approve, &backer, &[&backer]));
+        let reject = cast_vote_ix(&fundraiser, &milestones, &critic.pubkey(), 0, false);
+        assert!(send_ix(&mut svm, reject, &critic, &[&critic]));
+
+        // a contributor can only vote once per tranche
+        svm.expire_blockhash();
+        let again = cast_vote_ix(&fundraiser, &milestones, &backer.pubkey(), 0, true);
+        assert!(!send_ix(&mut svm, again, &backer, &[&backer]));
+
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+        let claim_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(fundraiser, false),
+                AccountMeta::new(milestones, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(maker_ata, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+            data: vec![4u8],
+        };
+
+        // the release has to wait for the voting window to close
+        assert!(!send_ix(&mut svm, claim_ix.clone(), &payer, &[&payer]));
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += voting_period as i64;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+
+        assert!(send_ix(&mut svm, claim_ix, &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_ata), amount_to_raise);
+
+        // the last tranche is out, so the fundraiser and its milestones are closed
+        for account in [fundraiser, milestones] {
+            assert!(
+                svm.get_account(&account)
+                    .map_or(true, |account| account.data.is_empty())
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_reopen_rejected_tranche_vote() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let (fundraiser, _, vault) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let milestones = Pubkey::find_program_address(
+            &[b"milestones".as_ref(), fundraiser.as_ref()],
+            &program_id(),
+        )
+        .0;
+
+        let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+        let voting_period: u64 = 3_600;
+        let amount_to_raise: u64 = 1_000_000;
+        let data = [
+            bytemuck::bytes_of(&initialize_data(
+                amount_to_raise,
+                7,
+                crate::state::FLAG_MILESTONES,
+            ))
+            .to_vec(),
+            milestones_data(&[(100, now)], 50, 60, voting_period),
+        ]
+        .concat();
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            data,
+            vec![AccountMeta::new(milestones, false)],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (backer, backer_ata) = new_contributor(&mut svm, &payer, &mint, 600_000);
+        let (critic, critic_ata) = new_contributor(&mut svm, &payer, &mint, 400_000);
+        for (contributor, contributor_ata, amount) in [
+            (&backer, backer_ata, 600_000),
+            (&critic, critic_ata, 400_000),
+        ] {
+            let contribute = contribute_ix(
+                &payer.pubkey(),
+                &mint,
+                &contributor.pubkey(),
+                &contributor_ata,
+                amount,
+                vec![],
+            );
+            assert!(send_ix(&mut svm, contribute, contributor, &[contributor]));
+        }
+
+        // only the critic votes, so the tranche is rejected
+        let reject = cast_vote_ix(&fundraiser, &milestones, &critic.pubkey(), 0, false);
+        assert!(send_ix(&mut svm, reject.clone(), &critic, &[&critic]));
+
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+        let claim_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(fundraiser, false),
+                AccountMeta::new(milestones, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(maker_ata, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+            data: vec![4u8],
+        };
+        let reopen_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(payer.pubkey(), true),
+                AccountMeta::new_readonly(fundraiser, false),
+                AccountMeta::new(milestones, false),
+            ],
+            data: vec![28u8],
+        };
+
+        // the vote cannot be re-opened while it is still running
+        assert!(!send_ix(&mut svm, reopen_ix.clone(), &payer, &[&payer]));
+
+        advance_clock(&mut svm, voting_period as i64);
+        assert!(!send_ix(&mut svm, claim_ix.clone(), &payer, &[&payer]));
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, reopen_ix.clone(), &payer, &[&payer]));
+
+        // everyone votes again, each contributor still only once
+        let approve = cast_vote_ix(&fundraiser, &milestones, &backer.pubkey(), 0, true);
+        assert!(send_ix(&mut svm, approve, &backer, &[&backer]));
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, reject.clone(), &critic, &[&critic]));
+        svm.expire_blockhash();
+        assert!(!send_ix(&mut svm, reject, &critic, &[&critic]));
+
+        // an approved tranche is released and its vote stays closed
+        advance_clock(&mut svm, voting_period as i64);
+        svm.expire_blockhash();
+        assert!(!send_ix(&mut svm, reopen_ix, &payer, &[&payer]));
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, claim_ix, &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_ata), amount_to_raise);
+    }
+
+    #[test]
+    pub fn test_tranche_vote_locks_refund() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let milestones = Pubkey::find_program_address(
+            &[b"milestones".as_ref(), fundraiser.as_ref()],
+            &program_id(),
+        )
+        .0;
+
+        // voting on the tranche opens a minute before the deadline
+        let deadline = svm.get_sysvar::<Clock>().unix_timestamp as u64 + 86_400;
+        let voting_period: u64 = 3_600;
+        let data = [
+            bytemuck::bytes_of(&initialize_data(
+                1_000_000,
+                1,
+                crate::state::FLAG_MILESTONES,
+            ))
+            .to_vec(),
+            milestones_data(&[(100, deadline - 60)], 50, 60, voting_period),
+        ]
+        .concat();
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            data,
+            vec![AccountMeta::new(milestones, false)],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (contributor, contributor_ata) = new_contributor(&mut svm, &payer, &mint, 400_000);
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+            400_000,
+            vec![],
+        );
+        assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+
+        advance_clock(&mut svm, 86_400 - 60);
+        let vote = cast_vote_ix(&fundraiser, &milestones, &contributor.pubkey(), 0, true);
+        assert!(send_ix(&mut svm, vote, &contributor, &[&contributor]));
+
+        // the raise failed, but the weight stays put until the voting window closes
+        advance_clock(&mut svm, 60);
+        let refund = refund_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+        );
+        assert!(!send_ix(
+            &mut svm,
+            refund.clone(),
+            &contributor,
+            &[&contributor]
+        ));
+
+        advance_clock(&mut svm, voting_period as i64);
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, refund, &contributor, &[&contributor]));
+        assert_eq!(token_balance(&svm, &contributor_ata), 400_000);
+    }
+
+    fn cancel_vote_ix(fundraiser: &Pubkey, contributor: &Pubkey, discriminator: u8) -> Instruction {
+        let (contributor_account, _) = contributor_pda(fundraiser, contributor);
+        let cancel_proposal =
+            Pubkey::find_program_address(&[b"cancel".as_ref(), fundraiser.as_ref()], &program_id())
+                .0;
+        let cancel_vote_record = Pubkey::find_program_address(
+            &[
+                b"cancel_vote".as_ref(),
+                fundraiser.as_ref(),
+                contributor.as_ref(),
+            ],
+            &program_id(),
+        )
+        .0;
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*contributor, true),
+                AccountMeta::new(*fundraiser, false),
+                AccountMeta::new(contributor_account, false),
+                AccountMeta::new(cancel_proposal, false),
+                AccountMeta::new(cancel_vote_record, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+            ],
+            data: vec![discriminator],
+        }
+    }
+
+    #[test]
+    pub fn test_contributor_cancellation() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+
+        let amount_to_raise: u64 = 1_000_000;
+        let mut ix_data = initialize_data(amount_to_raise, 7, 0);
+        // contributors holding half of the raise can cancel
+        ix_data.set_cancel_threshold(50);
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            bytemuck::bytes_of(&ix_data).to_vec(),
+            vec![],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (first, first_ata) = new_contributor(&mut svm, &payer, &mint, 400_000);
+        let (second, second_ata) = new_contributor(&mut svm, &payer, &mint, 300_000);
+        for (contributor, contributor_ata, amount) in
+            [(&first, first_ata, 400_000), (&second, second_ata, 300_000)]
+        {
+            let contribute = contribute_ix(
+                &payer.pubkey(),
+                &mint,
+                &contributor.pubkey(),
+                &contributor_ata,
+                amount,
+                vec![],
+            );
+            assert!(send_ix(&mut svm, contribute, contributor, &[contributor]));
+        }
+
+        // 400k out of 700k is above the threshold, so the proposal cancels right away
+        let propose = cancel_vote_ix(&fundraiser, &first.pubkey(), 7);
+        assert!(send_ix(&mut svm, propose, &first, &[&first]));
+
+        let fundraiser_account = svm.get_account(&fundraiser).unwrap();
+        let fundraiser_state =
+            bytemuck::from_bytes::<crate::state::FundraiserData>(&fundraiser_account.data);
+        assert_eq!(
+            fundraiser_state.status().unwrap(),
+            crate::state::FundraiserStatus::Cancelled
+        );
+
+        // voting on a cancelled fundraiser is rejected
+        let vote = cancel_vote_ix(&fundraiser, &second.pubkey(), 8);
+        assert!(!send_ix(&mut svm, vote, &second, &[&second]));
+
+        let refund = refund_ix(&payer.pubkey(), &mint, &second.pubkey(), &second_ata);
+        assert!(send_ix(&mut svm, refund, &second, &[&second]));
+        assert_eq!(token_balance(&svm, &second_ata), 300_000);
+    }
+
+    #[test]
+    pub fn test_refund_withdraws_cancel_vote() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+
+        let mut ix_data = initialize_data(1_000_000, 1, 0);
+        ix_data.set_cancel_threshold(60);
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            bytemuck::bytes_of(&ix_data).to_vec(),
+            vec![],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (first, first_ata) = new_contributor(&mut svm, &payer, &mint, 300_000);
+        let (second, second_ata) = new_contributor(&mut svm, &payer, &mint, 100_000);
+        let (third, third_ata) = new_contributor(&mut svm, &payer, &mint, 200_000);
+        for (contributor, contributor_ata, amount) in [
+            (&first, first_ata, 300_000),
+            (&second, second_ata, 100_000),
+            (&third, third_ata, 200_000),
+        ] {
+            let contribute = contribute_ix(
+                &payer.pubkey(),
+                &mint,
+                &contributor.pubkey(),
+                &contributor_ata,
+                amount,
+                vec![],
+            );
+            assert!(send_ix(&mut svm, contribute, contributor, &[contributor]));
+        }
+
+        // 300k out of 600k stays under the threshold
+        let propose = cancel_vote_ix(&fundraiser, &first.pubkey(), 7);
+        let cancel_accounts = propose.accounts[3..5].to_vec();
+        assert!(send_ix(&mut svm, propose, &first, &[&first]));
+
+        // the vote does not unlock a refund before the raise failed
+        let mut refund = refund_ix(&payer.pubkey(), &mint, &first.pubkey(), &first_ata);
+        assert!(!send_ix(&mut svm, refund.clone(), &first, &[&first]));
+
+        // once it failed, the refund must take the weight out of the proposal with it
+        advance_clock(&mut svm, 86_400);
+        svm.expire_blockhash();
+        assert!(!send_ix(&mut svm, refund.clone(), &first, &[&first]));
+        refund.accounts.extend(cancel_accounts.clone());
+        assert!(send_ix(&mut svm, refund, &first, &[&first]));
+        assert_eq!(token_balance(&svm, &first_ata), 300_000);
+
+        let proposal = svm.get_account(&cancel_accounts[0].pubkey).unwrap();
+        let proposal_state =
+            bytemuck::from_bytes::<crate::state::CancelProposalData>(&proposal.data);
+        assert_eq!(proposal_state.votes(), 0);
+
+        // the second wallet only brings its own weight, 100k out of the 300k left
+        let vote = cancel_vote_ix(&fundraiser, &second.pubkey(), 8);
+        assert!(send_ix(&mut svm, vote, &second, &[&second]));
+
+        let proposal = svm.get_account(&cancel_accounts[0].pubkey).unwrap();
+        let proposal_state =
+            bytemuck::from_bytes::<crate::state::CancelProposalData>(&proposal.data);
+        assert_eq!(proposal_state.votes(), 100_000);
+
+        let fundraiser_account = svm.get_account(&fundraiser).unwrap();
+        let fundraiser_state =
+            bytemuck::from_bytes::<crate::state::FundraiserData>(&fundraiser_account.data);
+        assert_eq!(
+            fundraiser_state.status().unwrap(),
+            crate::state::FundraiserStatus::Active
+        );
+    }
+
+    fn claim_ix(maker: &Pubkey, mint: &Pubkey, maker_ata: &Pubkey) -> Instruction {
+        let (fundraiser, _, vault) = fundraiser_accounts(maker, mint);
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*maker, true),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new(fundraiser, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(*maker_ata, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+                AccountMeta::new_readonly(
+                    ASSOCIATED_TOKEN_PROGRAM_ID.parse::<Pubkey>().unwrap(),
+                    false,
+                ),
+                AccountMeta::new_readonly(Rent::id(), false),
+            ],
+            data: vec![3u8],
+        }
+    }
+
+    #[test]
+    pub fn test_vesting_claim() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let amount_to_raise: u64 = 1_000_000;
+        let duration: u8 = 1;
+        let vesting_duration: u64 = 1_000;
+
+        let mut vesting = crate::state::VestingIxData::default();
+        vesting.set_cliff(100);
+        vesting.set_duration(vesting_duration);
+        let data = [
+            bytemuck::bytes_of(&initialize_data(
+                amount_to_raise,
+                duration,
+                crate::state::FLAG_VESTING,
+            ))
+            .to_vec(),
+            bytemuck::bytes_of(&vesting).to_vec(),
+        ]
+        .concat();
+        let init_ix = initialize_ix(&payer.pubkey(), &mint, data, vec![]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (contributor, contributor_ata) =
+            new_contributor(&mut svm, &payer, &mint, amount_to_raise);
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+            amount_to_raise,
+            vec![],
+        );
+        assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        // vesting starts at the deadline
+        let deadline = svm.get_sysvar::<Clock>().unix_timestamp + 86_400 * duration as i64;
+        let mut clock = svm.get_sysvar::<Clock>();
+
+        // still before the cliff
+        clock.unix_timestamp = deadline + 50;
+        svm.set_sysvar::<Clock>(&clock);
+        let claim = claim_ix(&payer.pubkey(), &mint, &maker_ata);
+        assert!(!send_ix(&mut svm, claim, &payer, &[&payer]));
+
+        // the raise is closed to new contributions once vesting has started
+        let (late, late_ata) = new_contributor(&mut svm, &payer, &mint, 100_000);
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &late.pubkey(),
+            &late_ata,
+            100_000,
+            vec![],
+        );
+        assert!(!send_ix(&mut svm, contribute, &late, &[&late]));
+
+        clock.unix_timestamp = deadline + 250;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+        let claim = claim_ix(&payer.pubkey(), &mint, &maker_ata);
+        assert!(send_ix(&mut svm, claim, &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_ata), 250_000);
+
+        // what has not vested yet stays with the maker's schedule
+        let refund = refund_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+        );
+        assert!(!send_ix(&mut svm, refund, &contributor, &[&contributor]));
+
+        clock.unix_timestamp = deadline + vesting_duration as i64;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+        let claim = claim_ix(&payer.pubkey(), &mint, &maker_ata);
+        assert!(send_ix(&mut svm, claim, &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_ata), amount_to_raise);
+
+        // the schedule is fully released, so the fundraiser is closed
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+        assert!(
+            svm.get_account(&fundraiser)
+                .map_or(true, |account| account.data.is_empty())
+        );
+    }
+
+    fn native_vault(fundraiser: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"vault".as_ref(), fundraiser.as_ref()], &program_id()).0
+    }
+
+    #[test]
+    pub fn test_native_fundraiser() {
+        let (mut svm, payer) = setup();
+
+        // native fundraisers have no mint, any account can fill the mint and ATA slots
+        let no_mint = Pubkey::new_unique();
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &no_mint);
+        let vault = native_vault(&fundraiser);
+        let amount_to_raise = LAMPORTS_PER_SOL;
+
+        let data = bytemuck::bytes_of(&initialize_data(
+            amount_to_raise,
+            1,
+            crate::state::FLAG_NATIVE,
+        ))
+        .to_vec();
+        let mut init_ix = initialize_ix(&payer.pubkey(), &no_mint, data, vec![]);
+        init_ix.accounts[3] = AccountMeta::new(vault, false);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let vault_rent = svm.get_account(&vault).unwrap().lamports;
+
+        let contribute = |svm: &mut LiteSVM, contributor: &Keypair, amount: u64| {
+            let mut ix = contribute_ix(
+                &payer.pubkey(),
+                &no_mint,
+                &contributor.pubkey(),
+                &no_mint,
+                amount,
+                vec![],
+            );
+            ix.accounts[5] = AccountMeta::new(vault, false);
+            svm.expire_blockhash();
+            send_ix(svm, ix, contributor, &[contributor])
+        };
+
+        let first = Keypair::new();
+        let second = Keypair::new();
+        svm.airdrop(&first.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+        svm.airdrop(&second.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        assert!(contribute(&mut svm, &first, amount_to_raise / 2));
+        assert!(contribute(&mut svm, &second, amount_to_raise / 2));
+        assert_eq!(
+            svm.get_account(&vault).unwrap().lamports,
+            vault_rent + amount_to_raise
+        );
+
+        // the final claim sweeps the vault, including its rent, and closes it
+        let before = svm.get_balance(&payer.pubkey()).unwrap();
+        let fundraiser_rent = svm.get_account(&fundraiser).unwrap().lamports;
+        let mut claim = claim_ix(&payer.pubkey(), &no_mint, &no_mint);
+        claim.accounts[3] = AccountMeta::new(vault, false);
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, claim, &payer, &[&payer]));
+        assert_eq!(
+            svm.get_balance(&payer.pubkey()).unwrap(),
+            before + amount_to_raise + vault_rent + fundraiser_rent - 5_000
+        );
+        assert_eq!(svm.get_balance(&vault).unwrap_or(0), 0);
+    }
+
+    #[test]
+    pub fn test_native_refund() {
+        let (mut svm, payer) = setup();
+
+        let no_mint = Pubkey::new_unique();
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &no_mint);
+        let vault = native_vault(&fundraiser);
+        let amount_to_raise = LAMPORTS_PER_SOL;
+
+        let data = bytemuck::bytes_of(&initialize_data(
+            amount_to_raise,
+            1,
+            crate::state::FLAG_NATIVE,
+        ))
+        .to_vec();
+        let mut init_ix = initialize_ix(&payer.pubkey(), &no_mint, data, vec![]);
+        init_ix.accounts[3] = AccountMeta::new(vault, false);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let vault_rent = svm.get_account(&vault).unwrap().lamports;
+
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 2 * LAMPORTS_PER_SOL)
+            .unwrap();
+        let mut contribute = contribute_ix(
+            &payer.pubkey(),
+            &no_mint,
+            &contributor.pubkey(),
+            &no_mint,
+            amount_to_raise / 2,
+            vec![],
+        );
+        contribute.accounts[5] = AccountMeta::new(vault, false);
+        assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+
+        // only half of the target was raised by the deadline
+        advance_clock(&mut svm, 86_400);
+
+        // refunds are paid in lamports straight back to the contributor
+        let before = svm.get_balance(&contributor.pubkey()).unwrap();
+        let mut refund = refund_ix(&payer.pubkey(), &no_mint, &contributor.pubkey(), &no_mint);
+        refund.accounts[6] = AccountMeta::new(vault, false);
+        assert!(send_ix(&mut svm, refund, &contributor, &[&contributor]));
+        assert_eq!(
+            svm.get_balance(&contributor.pubkey()).unwrap(),
+            before + amount_to_raise / 2 - 5_000
+        );
+        assert_eq!(svm.get_account(&vault).unwrap().lamports, vault_rent);
+    }
+
+    #[test]
+    pub fn test_wrapped_sol_contribution() {
+        let (mut svm, payer) = setup();
+
+        let mint = spl_token::native_mint::ID;
+        let (_, _, vault) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let amount_to_raise = LAMPORTS_PER_SOL;
+
+        let data = bytemuck::bytes_of(&initialize_data(amount_to_raise, 1, 0)).to_vec();
+        let init_ix = initialize_ix(&payer.pubkey(), &mint, data, vec![]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        // the contributor only holds SOL, no wSOL account is needed to contribute
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 2 * LAMPORTS_PER_SOL)
+            .unwrap();
+
+        let amount = LAMPORTS_PER_SOL / 2;
+        let mut contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor.pubkey(),
+            amount,
+            vec![],
+        );
+        contribute.data[11] = 1; // wrap
+        assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+        assert_eq!(token_balance(&svm, &vault), amount);
+
+        // refund into a temporary wSOL account that gets closed back into SOL
+        let temp_ata = CreateAssociatedTokenAccount::new(&mut svm, &contributor, &mint)
+            .owner(&contributor.pubkey())
+            .send()
+            .unwrap();
+        let temp_rent = svm.get_balance(&temp_ata).unwrap();
+        let before = svm.get_balance(&contributor.pubkey()).unwrap();
+
+        // half of the target was raised when the deadline passed
+        advance_clock(&mut svm, 86_400);
+        let mut refund = refund_ix(&payer.pubkey(), &mint, &contributor.pubkey(), &temp_ata);
+        refund.data.extend([1, 0, 0, 0, 0, 0, 0, 0]); // unwrap
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, refund, &contributor, &[&contributor]));
+
+        assert_eq!(token_balance(&svm, &vault), 0);
+        assert_eq!(svm.get_balance(&temp_ata).unwrap_or(0), 0);
+        assert_eq!(
+            svm.get_balance(&contributor.pubkey()).unwrap(),
+            before + amount + temp_rent - 5_000
+        );
+    }
+
+    fn accepted_mints_pda(fundraiser: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"accepted_mints".as_ref(), fundraiser.as_ref()],
+            &program_id(),
+        )
+        .0
+    }
+
+    fn accepted_mint_data(rate: u64, rate_decimals: u8) -> crate::state::AcceptedMintIxData {
+        let mut ix_data = crate::state::AcceptedMintIxData::default();
+        ix_data.set_rate(rate);
+        ix_data.set_rate_decimals(rate_decimals);
+        ix_data.set_padding();
+        ix_data
+    }
+
+    fn register_mint_ix(
+        maker: &Pubkey,
+        primary_mint: &Pubkey,
+        mint: &Pubkey,
+        rate: u64,
+        rate_decimals: u8,
+    ) -> Instruction {
+        let (fundraiser, _, _) = fundraiser_accounts(maker, primary_mint);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser, mint);
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*maker, true),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new_readonly(fundraiser, false),
+                AccountMeta::new(accepted_mints_pda(&fundraiser), false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(
+                    ASSOCIATED_TOKEN_PROGRAM_ID.parse::<Pubkey>().unwrap(),
+                    false,
+                ),
+            ],
+            data: [
+                vec![9u8],
+                bytemuck::bytes_of(&accepted_mint_data(rate, rate_decimals)).to_vec(),
+            ]
+            .concat(),
+        }
+    }
+
+    #[test]
+    pub fn test_multi_mint_fundraiser() {
+        let (mut svm, payer) = setup();
+
+        let usdc = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let usdt = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let (fundraiser, _, usdc_vault) = fundraiser_accounts(&payer.pubkey(), &usdc);
+        let usdt_vault =
+            spl_associated_token_account::get_associated_token_address(&fundraiser, &usdt);
+        let accepted_mints = AccountMeta::new(accepted_mints_pda(&fundraiser), false);
+        let amount_to_raise: u64 = 1_000_000;
+
+        // usdc counts one to one, usdt at 0.98
+        let data = [
+            bytemuck::bytes_of(&initialize_data(
+                amount_to_raise,
+                1,
+                crate::state::FLAG_MULTI_MINT,
+            ))
+            .to_vec(),
+            bytemuck::bytes_of(&accepted_mint_data(1, 0)).to_vec(),
+        ]
+        .concat();
+        let init_ix = initialize_ix(&payer.pubkey(), &usdc, data, vec![accepted_mints.clone()]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let register = register_mint_ix(&payer.pubkey(), &usdc, &usdt, 98, 2);
+        assert!(send_ix(&mut svm, register, &payer, &[&payer]));
+
+        let contribute = |svm: &mut LiteSVM, mint: &Pubkey, vault: &Pubkey, amount: u64| {
+            let (contributor, contributor_ata) = new_contributor(svm, &payer, mint, amount);
+            let mut ix = contribute_ix(
+                &payer.pubkey(),
+                mint,
+                &contributor.pubkey(),
+                &contributor_ata,
+                amount,
+                vec![accepted_mints.clone()],
+            );
+            ix.accounts[5] = AccountMeta::new(*vault, false);
+            assert!(send_ix(svm, ix, &contributor, &[&contributor]));
+            (contributor, contributor_ata)
+        };
+
+        contribute(&mut svm, &usdc, &usdc_vault, 510_000);
+        contribute(&mut svm, &usdt, &usdt_vault, 500_000);
+        assert_eq!(token_balance(&svm, &usdt_vault), 500_000);
+
+        // 510_000 + 490_000 normalized units meets the target
+        let current_amount = |svm: &LiteSVM| {
+            let data = svm.get_account(&fundraiser).unwrap().data;
+            u64::from_le_bytes(data[72..80].try_into().unwrap())
+        };
+        assert_eq!(current_amount(&svm), amount_to_raise);
+
+        let maker_usdc = CreateAssociatedTokenAccount::new(&mut svm, &payer, &usdc)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+        let maker_usdt = CreateAssociatedTokenAccount::new(&mut svm, &payer, &usdt)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let mut claim = claim_ix(&payer.pubkey(), &usdc, &maker_usdc);
+        claim.accounts.extend([
+            accepted_mints.clone(),
+            AccountMeta::new(usdt_vault, false),
+            AccountMeta::new(maker_usdt, false),
+        ]);
+        assert!(send_ix(&mut svm, claim, &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_usdc), 510_000);
+        assert_eq!(token_balance(&svm, &maker_usdt), 500_000);
+    }
+
+    #[test]
+    pub fn test_multi_mint_refund() {
+        let (mut svm, payer) = setup();
+
+        let usdc = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let usdt = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let (fundraiser, _, usdc_vault) = fundraiser_accounts(&payer.pubkey(), &usdc);
+        let usdt_vault =
+            spl_associated_token_account::get_associated_token_address(&fundraiser, &usdt);
+        let accepted_mints = AccountMeta::new(accepted_mints_pda(&fundraiser), false);
+
+        let data = [
+            bytemuck::bytes_of(&initialize_data(
+                1_000_000,
+                1,
+                crate::state::FLAG_MULTI_MINT,
+            ))
+            .to_vec(),
+            bytemuck::bytes_of(&accepted_mint_data(1, 0)).to_vec(),
+        ]
+        .concat();
+        let init_ix = initialize_ix(&payer.pubkey(), &usdc, data, vec![accepted_mints.clone()]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let register = register_mint_ix(&payer.pubkey(), &usdc, &usdt, 98, 2);
+        assert!(send_ix(&mut svm, register, &payer, &[&payer]));
+
+        let mut contributors = vec![];
+        for (mint, vault, amount) in [(usdc, usdc_vault, 300_000), (usdt, usdt_vault, 500_000)] {
+            let (contributor, contributor_ata) = new_contributor(&mut svm, &payer, &mint, amount);
+            let mut ix = contribute_ix(
+                &payer.pubkey(),
+                &mint,
+                &contributor.pubkey(),
+                &contributor_ata,
+                amount,
+                vec![accepted_mints.clone()],
+            );
+            ix.accounts[5] = AccountMeta::new(vault, false);
+            assert!(send_ix(&mut svm, ix, &contributor, &[&contributor]));
+            contributors.push((contributor, contributor_ata));
+        }
+
+        // 300_000 + 490_000 normalized units miss the target
+        advance_clock(&mut svm, 86_400);
+
+        // the usdt contributor is refunded in usdt
+        let (usdt_contributor, usdt_ata) = &contributors[1];
+        let mut refund = refund_ix(&payer.pubkey(), &usdt, &usdt_contributor.pubkey(), usdt_ata);
+        refund.accounts[6] = AccountMeta::new(usdt_vault, false);
+        refund.accounts.push(accepted_mints.clone());
+        assert!(send_ix(
+            &mut svm,
+            refund,
+            usdt_contributor,
+            &[usdt_contributor]
+        ));
+        assert_eq!(token_balance(&svm, usdt_ata), 500_000);
+        assert_eq!(token_balance(&svm, &usdt_vault), 0);
+
+        let data = svm.get_account(&fundraiser).unwrap().data;
+        assert_eq!(
+            u64::from_le_bytes(data[72..80].try_into().unwrap()),
+            300_000
+        );
+    }
+
+    /// Writes a mock account in the Pyth v2 price account layout.
+    fn set_price_account(
+        svm: &mut LiteSVM,
+        price_account: &Pubkey,
+        price: i64,
+        conf: u64,
+        expo: i32,
+    ) {
+        let mut data = vec![0u8; 240];
+        data[0..4].copy_from_slice(&0xa1b2c3d4u32.to_le_bytes());
+        data[4..8].copy_from_slice(&2u32.to_le_bytes());
+        data[8..12].copy_from_slice(&3u32.to_le_bytes());
+        data[20..24].copy_from_slice(&expo.to_le_bytes());
+        let now = svm.get_sysvar::<Clock>().unix_timestamp;
+        data[96..104].copy_from_slice(&now.to_le_bytes());
+        data[208..216].copy_from_slice(&price.to_le_bytes());
+        data[216..224].copy_from_slice(&conf.to_le_bytes());
+        data[224..228].copy_from_slice(&1u32.to_le_bytes());
+
+        svm.set_account(
+            *price_account,
+            solana_account::Account {
+                lamports: LAMPORTS_PER_SOL,
+                data,
+                owner: Pubkey::new_unique(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    pub fn test_oracle_priced_fundraiser() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(9)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        // $150.00000000 per token
+        let price_account = Pubkey::new_unique();
+        set_price_account(&mut svm, &price_account, 15_000_000_000, 10_000_000, -8);
+
+        // goal of $250 with 6 decimals
+        let amount_to_raise: u64 = 250_000_000;
+        let mut oracle = crate::state::OracleIxData::default();
+        oracle.set_price_account(&price_account.to_bytes());
+        let data = [
+            bytemuck::bytes_of(&initialize_data(
+                amount_to_raise,
+                1,
+                crate::state::FLAG_ORACLE,
+            ))
+            .to_vec(),
+            bytemuck::bytes_of(&oracle).to_vec(),
+        ]
+        .concat();
+        let init_ix = initialize_ix(&payer.pubkey(), &mint, data, vec![]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (contributor, contributor_ata) =
+            new_contributor(&mut svm, &payer, &mint, 2_000_000_000);
+        let price_meta = AccountMeta::new_readonly(price_account, false);
+
+        // a price that was not refreshed for two minutes is rejected
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 120;
+        svm.set_sysvar::<Clock>(&clock);
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+            2_000_000_000,
+            vec![price_meta.clone()],
+        );
+        assert!(!send_ix(
+            &mut svm,
+            contribute.clone(),
+            &contributor,
+            &[&contributor]
+        ));
+
+        // two tokens at $150 are credited as $300
+        set_price_account(&mut svm, &price_account, 15_000_000_000, 10_000_000, -8);
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+
+        let (fundraiser, _, vault) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let data = svm.get_account(&fundraiser).unwrap().data;
+        assert_eq!(
+            u64::from_le_bytes(data[72..80].try_into().unwrap()),
+            300_000_000
+        );
+        assert_eq!(token_balance(&svm, &vault), 2_000_000_000);
+
+        // the claim compares the credited USD amount with the goal
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+        let claim = claim_ix(&payer.pubkey(), &mint, &maker_ata);
+        assert!(send_ix(&mut svm, claim, &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_ata), 2_000_000_000);
+    }
+
+    #[test]
+    pub fn test_allowlisted_contributions() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let (allowed, allowed_ata) = new_contributor(&mut svm, &payer, &mint, 1_000_000);
+        let (outsider, outsider_ata) = new_contributor(&mut svm, &payer, &mint, 1_000_000);
+
+        let cap = 300_000;
+        let entries = vec![
+            (Pubkey::new_unique().to_bytes(), 0),
+            (allowed.pubkey().to_bytes(), cap),
+            (Pubkey::new_unique().to_bytes(), 0),
+        ];
+        let tree = super::merkle::MerkleTree::new(&entries);
+
+        let mut allowlist = crate::state::AllowlistIxData::default();
+        allowlist.merkle_root = tree.root();
+        let data = [
+            bytemuck::bytes_of(&initialize_data(1_000_000, 1, crate::state::FLAG_ALLOWLIST))
+                .to_vec(),
+            bytemuck::bytes_of(&allowlist).to_vec(),
+        ]
+        .concat();
+        let init_ix = initialize_ix(&payer.pubkey(), &mint, data, vec![]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let contribute = |contributor: &Keypair, ata: &Pubkey, amount: u64, cap: u64| {
+            let proof = tree.proof(1);
+            let mut proof_ix = crate::state::AllowlistProofIxData::default();
+            proof_ix.set_cap(cap);
+            proof_ix.set_depth(proof.len() as u8);
+            proof_ix.set_padding();
+
+            let mut ix = contribute_ix(
+                &payer.pubkey(),
+                &mint,
+                &contributor.pubkey(),
+                ata,
+                amount,
+                vec![],
+            );
+            ix.data.extend(bytemuck::bytes_of(&proof_ix));
+            ix.data.extend(proof.concat());
+            ix
+        };
+
+        // the proof only holds for the allowlisted contributor and their own cap
+        let ix = contribute(&outsider, &outsider_ata, 100_000, cap);
+        assert!(!send_ix(&mut svm, ix, &outsider, &[&outsider]));
+        let ix = contribute(&allowed, &allowed_ata, 100_000, 0);
+        assert!(!send_ix(&mut svm, ix, &allowed, &[&allowed]));
+
+        let ix = contribute(&allowed, &allowed_ata, 200_000, cap);
+        assert!(send_ix(&mut svm, ix, &allowed, &[&allowed]));
+
+        // 200_000 + 200_000 is above the cap
+        svm.expire_blockhash();
+        let ix = contribute(&allowed, &allowed_ata, 200_000, cap);
+        assert!(!send_ix(&mut svm, ix, &allowed, &[&allowed]));
+
+        let ix = contribute(&allowed, &allowed_ata, 100_000, cap);
+        assert!(send_ix(&mut svm, ix, &allowed, &[&allowed]));
+        assert_eq!(token_balance(&svm, &allowed_ata), 700_000);
+    }
+
+    #[test]
+    pub fn test_token_gated_contributions() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let membership = CreateMint::new(&mut svm, &payer)
+            .decimals(0)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let mut gate = crate::state::TokenGateIxData::default();
+        gate.set_gate_mint(&membership.to_bytes());
+        gate.set_min_balance(2);
+        let data = [
+            bytemuck::bytes_of(&initialize_data(
+                1_000_000,
+                1,
+                crate::state::FLAG_TOKEN_GATE,
+            ))
+            .to_vec(),
+            bytemuck::bytes_of(&gate).to_vec(),
+        ]
+        .concat();
+        let init_ix = initialize_ix(&payer.pubkey(), &mint, data, vec![]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (contributor, contributor_ata) = new_contributor(&mut svm, &payer, &mint, 500_000);
+        let membership_ata = CreateAssociatedTokenAccount::new(&mut svm, &contributor, &membership)
+            .owner(&contributor.pubkey())
+            .send()
+            .unwrap();
+        MintTo::new(&mut svm, &payer, &membership, &membership_ata, 1)
+            .owner(&payer)
+            .send()
+            .unwrap();
+
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+            100_000,
+            vec![AccountMeta::new_readonly(membership_ata, false)],
+        );
+
+        // one membership token is below the minimum balance
+        assert!(!send_ix(
+            &mut svm,
+            contribute.clone(),
+            &contributor,
+            &[&contributor]
+        ));
+
+        MintTo::new(&mut svm, &payer, &membership, &membership_ata, 1)
+            .owner(&payer)
+            .send()
+            .unwrap();
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+        assert_eq!(token_balance(&svm, &contributor_ata), 400_000);
+
+        // a position only moves to a wallet that passes the gate too
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let (recipient, _) = new_contributor(&mut svm, &payer, &mint, 0);
+        let recipient_membership = CreateAssociatedTokenAccount::new(&mut svm, &payer, &membership)
+            .owner(&recipient.pubkey())
+            .send()
+            .unwrap();
+        let mut transfer = transfer_position_ix(
+            &fundraiser,
+            &contributor.pubkey(),
+            &recipient.pubkey(),
+            100_000,
+        );
+        transfer
+            .accounts
+            .push(AccountMeta::new_readonly(recipient_membership, false));
+        assert!(!send_ix(
+            &mut svm,
+            transfer.clone(),
+            &contributor,
+            &[&contributor]
+        ));
+
+        MintTo::new(&mut svm, &payer, &membership, &recipient_membership, 2)
+            .owner(&payer)
+            .send()
+            .unwrap();
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, transfer, &contributor, &[&contributor]));
+    }
+
+    fn issue_attestation_ix(issuer: &Pubkey, contributor: &Pubkey, expires_at: u64) -> Instruction {
+        let (attestation, _) = Pubkey::find_program_address(
+            &[
+                b"attestation".as_ref(),
+                issuer.as_ref(),
+                contributor.as_ref(),
+            ],
+            &program_id(),
+        );
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*issuer, true),
+                AccountMeta::new_readonly(*contributor, false),
+                AccountMeta::new(attestation, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+            ],
+            data: [vec![10u8], expires_at.to_le_bytes().to_vec()].concat(),
+        }
+    }
+
+    #[test]
+    pub fn test_attested_contributions() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        let issuer = Keypair::new();
+        svm.airdrop(&issuer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let mut attestation = crate::state::AttestationIxData::default();
+        attestation.set_issuer(&issuer.pubkey().to_bytes());
+        let data = [
+            bytemuck::bytes_of(&initialize_data(
+                1_000_000,
+                1,
+                crate::state::FLAG_ATTESTATION,
+            ))
+            .to_vec(),
+            bytemuck::bytes_of(&attestation).to_vec(),
+        ]
+        .concat();
+        let init_ix = initialize_ix(&payer.pubkey(), &mint, data, vec![]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (contributor, contributor_ata) = new_contributor(&mut svm, &payer, &mint, 500_000);
+        let (attestation_pda, _) = Pubkey::find_program_address(
+            &[
+                b"attestation".as_ref(),
+                issuer.pubkey().as_ref(),
+                contributor.pubkey().as_ref(),
+            ],
+            &program_id(),
+        );
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+            100_000,
+            vec![AccountMeta::new_readonly(attestation_pda, false)],
+        );
+
+        // not attested yet
+        assert!(!send_ix(
+            &mut svm,
+            contribute.clone(),
+            &contributor,
+            &[&contributor]
+        ));
+
+        let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+        let issue = issue_attestation_ix(&issuer.pubkey(), &contributor.pubkey(), now + 1_000);
+        assert!(send_ix(&mut svm, issue, &issuer, &[&issuer]));
+
+        svm.expire_blockhash();
+        assert!(send_ix(
+            &mut svm,
+            contribute.clone(),
+            &contributor,
+            &[&contributor]
+        ));
+
+        // the attestation no longer counts once it expired
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 1_000;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+        assert!(!send_ix(
+            &mut svm,
+            contribute,
+            &contributor,
+            &[&contributor]
+        ));
+    }
+
+    fn block_ix(
+        maker: &Pubkey,
+        mint: &Pubkey,
+        contributor: &Pubkey,
+        discriminator: u8,
+    ) -> Instruction {
+        let (fundraiser, _, _) = fundraiser_accounts(maker, mint);
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*maker, true),
+                AccountMeta::new(fundraiser, false),
+                AccountMeta::new_readonly(*contributor, false),
+                AccountMeta::new(block_record_pda(&fundraiser, contributor), false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+            ],
+            data: vec![discriminator],
+        }
+    }
+
+    fn block_record_pda(fundraiser: &Pubkey, contributor: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"block".as_ref(), fundraiser.as_ref(), contributor.as_ref()],
+            &program_id(),
+        )
+        .0
+    }
+
+    #[test]
+    pub fn test_blocked_contributor() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+
+        let data = bytemuck::bytes_of(&initialize_data(1_000_000, 1, 0)).to_vec();
+        let init_ix = initialize_ix(&payer.pubkey(), &mint, data, vec![]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (blocked, blocked_ata) = new_contributor(&mut svm, &payer, &mint, 500_000);
+        let (other, other_ata) = new_contributor(&mut svm, &payer, &mint, 500_000);
+
+        let contribute = |contributor: &Keypair, ata: &Pubkey, amount: u64| {
+            contribute_ix(
+                &payer.pubkey(),
+                &mint,
+                &contributor.pubkey(),
+                ata,
+                amount,
+                vec![AccountMeta::new_readonly(
+                    block_record_pda(&fundraiser, &contributor.pubkey()),
+                    false,
+                )],
+            )
+        };
+
+        assert!(send_ix(
+            &mut svm,
+            contribute(&blocked, &blocked_ata, 100_000),
+            &blocked,
+            &[&blocked]
+        ));
+
+        let block = block_ix(&payer.pubkey(), &mint, &blocked.pubkey(), 11);
+        assert!(send_ix(&mut svm, block, &payer, &[&payer]));
+
+        assert!(!send_ix(
+            &mut svm,
+            contribute(&blocked, &blocked_ata, 200_000),
+            &blocked,
+            &[&blocked]
+        ));
+        assert!(send_ix(
+            &mut svm,
+            contribute(&other, &other_ata, 200_000),
+            &other,
+            &[&other]
+        ));
+
+        let unblock = block_ix(&payer.pubkey(), &mint, &blocked.pubkey(), 12);
+        assert!(send_ix(&mut svm, unblock, &payer, &[&payer]));
+        svm.expire_blockhash();
+        assert!(send_ix(
+            &mut svm,
+            contribute(&blocked, &blocked_ata, 200_000),
+            &blocked,
+            &[&blocked]
+        ));
+
+        // a blocked wallet can still take its money back once the raise failed
+        let block = block_ix(&payer.pubkey(), &mint, &blocked.pubkey(), 11);
+        assert!(send_ix(&mut svm, block, &payer, &[&payer]));
+        advance_clock(&mut svm, 86_400);
+        let refund = refund_ix(&payer.pubkey(), &mint, &blocked.pubkey(), &blocked_ata);
+        assert!(send_ix(&mut svm, refund, &blocked, &[&blocked]));
+        assert_eq!(token_balance(&svm, &blocked_ata), 500_000);
+    }
+
+    #[test]
+    pub fn test_contribution_receipts() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let (receipt_mint, _) = Pubkey::find_program_address(
+            &[b"receipt".as_ref(), fundraiser.as_ref()],
+            &program_id(),
+        );
+
+        let data =
+            bytemuck::bytes_of(&initialize_data(1_000_000, 1, crate::state::FLAG_RECEIPT)).to_vec();
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            data,
+            vec![AccountMeta::new(receipt_mint, false)],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (contributor, contributor_ata) = new_contributor(&mut svm, &payer, &mint, 500_000);
+        let receipt_ata = CreateAssociatedTokenAccount::new(&mut svm, &contributor, &receipt_mint)
+            .owner(&contributor.pubkey())
+            .send()
+            .unwrap();
+        let receipt_accounts = vec![
+            AccountMeta::new(receipt_mint, false),
+            AccountMeta::new(receipt_ata, false),
+        ];
+
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+            100_000,
+            receipt_accounts.clone(),
+        );
+        assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+        assert_eq!(token_balance(&svm, &receipt_ata), 100_000);
+
+        advance_clock(&mut svm, 86_400);
+        let mut refund = refund_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+        );
+        refund.accounts.extend(receipt_accounts);
+        assert!(send_ix(&mut svm, refund, &contributor, &[&contributor]));
+        assert_eq!(token_balance(&svm, &contributor_ata), 500_000);
+        assert_eq!(token_balance(&svm, &receipt_ata), 0);
+    }
+
+    fn transfer_position_ix(
+        fundraiser: &Pubkey,
+        holder: &Pubkey,
+        recipient: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let (holder_account, _) = contributor_pda(fundraiser, holder);
+        let (recipient_account, _) = contributor_pda(fundraiser, recipient);
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*holder, true),
+                AccountMeta::new_readonly(*recipient, false),
+                AccountMeta::new_readonly(*fundraiser, false),
+                AccountMeta::new(holder_account, false),
+                AccountMeta::new(recipient_account, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+            data: [vec![13u8], amount.to_le_bytes().to_vec()].concat(),
+        }
+    }
+
+    #[test]
+    pub fn test_transfer_position() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+
+        let data = bytemuck::bytes_of(&initialize_data(1_000_000, 1, 0)).to_vec();
+        let init_ix = initialize_ix(&payer.pubkey(), &mint, data, vec![]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (holder, holder_ata) = new_contributor(&mut svm, &payer, &mint, 500_000);
+        let (recipient, recipient_ata) = new_contributor(&mut svm, &payer, &mint, 0);
+
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &holder.pubkey(),
+            &holder_ata,
+            300_000,
+            vec![],
+        );
+        assert!(send_ix(&mut svm, contribute, &holder, &[&holder]));
+
+        // more than the position holds
+        let transfer =
+            transfer_position_ix(&fundraiser, &holder.pubkey(), &recipient.pubkey(), 400_000);
+        assert!(!send_ix(&mut svm, transfer, &holder, &[&holder]));
+
+        let transfer =
+            transfer_position_ix(&fundraiser, &holder.pubkey(), &recipient.pubkey(), 100_000);
+        assert!(send_ix(&mut svm, transfer, &holder, &[&holder]));
+
+        // the raise is unchanged, each wallet refunds its own part of it
+        advance_clock(&mut svm, 86_400);
+
+        // once the raise failed, positions no longer move
+        svm.expire_blockhash();
+        let transfer =
+            transfer_position_ix(&fundraiser, &holder.pubkey(), &recipient.pubkey(), 100_000);
+        assert!(!send_ix(&mut svm, transfer, &holder, &[&holder]));
+
+        let refund = refund_ix(&payer.pubkey(), &mint, &recipient.pubkey(), &recipient_ata);
+        assert!(send_ix(&mut svm, refund, &recipient, &[&recipient]));
+        assert_eq!(token_balance(&svm, &recipient_ata), 100_000);
+
+        let refund = refund_ix(&payer.pubkey(), &mint, &holder.pubkey(), &holder_ata);
+        assert!(send_ix(&mut svm, refund, &holder, &[&holder]));
+        assert_eq!(token_balance(&svm, &holder_ata), 400_000);
+    }
+
+    fn claim_reward_ix(
+        fundraiser: &Pubkey,
+        reward_vault: &Pubkey,
+        contributor: &Pubkey,
+        contributor_reward: &Pubkey,
+    ) -> Instruction {
+        let (contributor_account, _) = contributor_pda(fundraiser, contributor);
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*contributor, true),
+                AccountMeta::new(*fundraiser, false),
+                AccountMeta::new(contributor_account, false),
+                AccountMeta::new(*reward_vault, false),
+                AccountMeta::new(*contributor_reward, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+            data: vec![14u8],
+        }
+    }
+
+    fn close_rewards_ix(
+        maker: &Pubkey,
+        fundraiser: &Pubkey,
+        reward_vault: &Pubkey,
+        maker_reward: &Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*maker, true),
+                AccountMeta::new(*fundraiser, false),
+                AccountMeta::new(*reward_vault, false),
+                AccountMeta::new(*maker_reward, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+            data: vec![27u8],
+        }
+    }
+
+    #[test]
+    pub fn test_reward_distribution() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let reward_mint = CreateMint::new(&mut svm, &payer)
+            .decimals(9)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let reward_vault =
+            spl_associated_token_account::get_associated_token_address(&fundraiser, &reward_mint);
+
+        let data =
+            bytemuck::bytes_of(&initialize_data(400_000, 1, crate::state::FLAG_REWARD)).to_vec();
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            data,
+            vec![
+                AccountMeta::new_readonly(reward_mint, false),
+                AccountMeta::new(reward_vault, false),
+            ],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        // the maker funds the rewards with a plain transfer
+        MintTo::new(&mut svm, &payer, &reward_mint, &reward_vault, 1_000)
+            .owner(&payer)
+            .send()
+            .unwrap();
+
+        // the last backer's share of the rewards rounds down to nothing
+        let mut contributors = vec![];
+        for amount in [300_000, 99_999, 1] {
+            let (contributor, contributor_ata) = new_contributor(&mut svm, &payer, &mint, amount);
+            let contribute = contribute_ix(
+                &payer.pubkey(),
+                &mint,
+                &contributor.pubkey(),
+                &contributor_ata,
+                amount,
+                vec![],
+            );
+            assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+
+            let contributor_reward =
+                CreateAssociatedTokenAccount::new(&mut svm, &contributor, &reward_mint)
+                    .owner(&contributor.pubkey())
+                    .send()
+                    .unwrap();
+            contributors.push((contributor, contributor_reward));
+        }
+
+        // nothing to share before the raise succeeded
+        let (first, first_reward) = &contributors[0];
+        let claim = claim_reward_ix(&fundraiser, &reward_vault, &first.pubkey(), first_reward);
+        assert!(!send_ix(&mut svm, claim, first, &[first]));
+
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+        let claim = claim_ix(&payer.pubkey(), &mint, &maker_ata);
+        assert!(send_ix(&mut svm, claim, &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_ata), 400_000);
+
+        let maker_reward = CreateAssociatedTokenAccount::new(&mut svm, &payer, &reward_mint)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+
+        for ((contributor, contributor_reward), expected) in contributors.iter().zip([750, 249, 0])
+        {
+            svm.expire_blockhash();
+            // the fundraiser stays open until the last reward is claimed
+            let close =
+                close_rewards_ix(&payer.pubkey(), &fundraiser, &reward_vault, &maker_reward);
+            assert!(!send_ix(&mut svm, close, &payer, &[&payer]));
+
+            let claim = claim_reward_ix(
+                &fundraiser,
+                &reward_vault,
+                &contributor.pubkey(),
+                contributor_reward,
+            );
+            assert!(send_ix(&mut svm, claim, contributor, &[contributor]));
+            assert_eq!(token_balance(&svm, contributor_reward), expected);
+        }
+
+        // a second claim is refused
+        svm.expire_blockhash();
+        let claim = claim_reward_ix(&fundraiser, &reward_vault, &first.pubkey(), first_reward);
+        assert!(!send_ix(&mut svm, claim, first, &[first]));
+        assert_eq!(token_balance(&svm, &reward_vault), 0);
+
+        assert_eq!(token_balance(&svm, &reward_vault), 1);
+
+        // every reward is claimed, so the maker can close the fundraiser and take the dust
+        let close = close_rewards_ix(&payer.pubkey(), &fundraiser, &reward_vault, &maker_reward);
+        assert!(send_ix(&mut svm, close, &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_reward), 1);
+        assert!(
+            svm.get_account(&fundraiser)
+                .map_or(true, |account| account.data.is_empty())
+        );
+    }
+
+    #[test]
+    pub fn test_reward_claim_period() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let reward_mint = CreateMint::new(&mut svm, &payer)
+            .decimals(9)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let reward_vault =
+            spl_associated_token_account::get_associated_token_address(&fundraiser, &reward_mint);
+
+        let data =
+            bytemuck::bytes_of(&initialize_data(400_000, 1, crate::state::FLAG_REWARD)).to_vec();
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            data,
+            vec![
+                AccountMeta::new_readonly(reward_mint, false),
+                AccountMeta::new(reward_vault, false),
+            ],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+        MintTo::new(&mut svm, &payer, &reward_mint, &reward_vault, 1_000)
+            .owner(&payer)
+            .send()
+            .unwrap();
+
+        let (contributor, contributor_ata) = new_contributor(&mut svm, &payer, &mint, 400_000);
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+            400_000,
+            vec![],
+        );
+        assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+        let claim = claim_ix(&payer.pubkey(), &mint, &maker_ata);
+        assert!(send_ix(&mut svm, claim, &payer, &[&payer]));
+
+        // the only backer never claims, which holds the rewards open until the period ends
+        let maker_reward = CreateAssociatedTokenAccount::new(&mut svm, &payer, &reward_mint)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+        let close = close_rewards_ix(&payer.pubkey(), &fundraiser, &reward_vault, &maker_reward);
+        assert!(!send_ix(&mut svm, close.clone(), &payer, &[&payer]));
+
+        advance_clock(
+            &mut svm,
+            (86_400 + crate::state::REWARD_CLAIM_PERIOD) as i64,
+        );
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, close, &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_reward), 1_000);
+        assert!(
+            svm.get_account(&fundraiser)
+                .map_or(true, |account| account.data.is_empty())
+        );
+    }
+
+    fn tiers_pda(fundraiser: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"tiers".as_ref(), fundraiser.as_ref()], &program_id()).0
+    }
+
+    #[test]
+    pub fn test_reward_tiers() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let tiers = tiers_pda(&fundraiser);
+
+        let mut tiers_data = crate::state::TiersIxData::default();
+        tiers_data.set_count(1);
+        tiers_data.tiers[0].set_price(100_000);
+        tiers_data.tiers[0].set_supply(1);
+        tiers_data.set_padding();
+        let data = [
+            bytemuck::bytes_of(&initialize_data(1_000_000, 1, crate::state::FLAG_TIERS)).to_vec(),
+            bytemuck::bytes_of(&tiers_data).to_vec(),
+        ]
+        .concat();
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            data,
+            vec![AccountMeta::new(tiers, false)],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (first, first_ata) = new_contributor(&mut svm, &payer, &mint, 500_000);
+        let (second, second_ata) = new_contributor(&mut svm, &payer, &mint, 500_000);
+
+        // the tier is stored as its index plus one, right after the wrap flag
+        let contribute_for_tier = |contributor: &Keypair, ata: &Pubkey, amount: u64| {
+            let mut ix = contribute_ix(
+                &payer.pubkey(),
+                &mint,
+                &contributor.pubkey(),
+                ata,
+                amount,
+                vec![AccountMeta::new(tiers, false)],
+            );
+            ix.data[12] = 1;
+            ix
+        };
+
+        // below the price of the tier
+        assert!(!send_ix(
+            &mut svm,
+            contribute_for_tier(&first, &first_ata, 50_000),
+            &first,
+            &[&first]
+        ));
+        assert!(send_ix(
+            &mut svm,
+            contribute_for_tier(&first, &first_ata, 100_000),
+            &first,
+            &[&first]
+        ));
+
+        // the only slot is taken
+        assert!(!send_ix(
+            &mut svm,
+            contribute_for_tier(&second, &second_ata, 100_000),
+            &second,
+            &[&second]
+        ));
+
+        // a refunded backer gives the slot back
+        assert!(send_ix(
+            &mut svm,
+            cancel_ix(&payer.pubkey(), &mint),
+            &payer,
+            &[&payer]
+        ));
+        let mut refund = refund_ix(&payer.pubkey(), &mint, &first.pubkey(), &first_ata);
+        refund.accounts.push(AccountMeta::new(tiers, false));
+        assert!(send_ix(&mut svm, refund, &first, &[&first]));
+        assert_eq!(token_balance(&svm, &first_ata), 500_000);
+
+        let data = svm.get_account(&tiers).unwrap().data;
+        let state = *bytemuck::from_bytes::<crate::state::TiersData>(&data);
+        assert_eq!(state.tiers[0].claimed(), 0);
+    }
+
+    #[test]
+    pub fn test_early_bird_rewards() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let reward_mint = CreateMint::new(&mut svm, &payer)
+            .decimals(9)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let reward_vault =
+            spl_associated_token_account::get_associated_token_address(&fundraiser, &reward_mint);
+
+        // +20% on the first contributions, gone after a day
+        let mut early_bird = crate::state::EarlyBirdIxData::default();
+        early_bird.set_bonus_period(86_400);
+        early_bird.set_bonus_bps(2_000);
+        early_bird.set_padding();
+        let data = [
+            bytemuck::bytes_of(&initialize_data(
+                200_000,
+                2,
+                crate::state::FLAG_REWARD | crate::state::FLAG_EARLY_BIRD,
+            ))
+            .to_vec(),
+            bytemuck::bytes_of(&early_bird).to_vec(),
+        ]
+        .concat();
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            data,
+            vec![
+                AccountMeta::new_readonly(reward_mint, false),
+                AccountMeta::new(reward_vault, false),
+            ],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        MintTo::new(&mut svm, &payer, &reward_mint, &reward_vault, 2_200)
+            .owner(&payer)
+            .send()
+            .unwrap();
+
+        let mut contributors = vec![];
+        for delay in [0, 86_400] {
+            let mut clock = svm.get_sysvar::<Clock>();
+            clock.unix_timestamp += delay;
+            svm.set_sysvar::<Clock>(&clock);
+
+            let (contributor, contributor_ata) = new_contributor(&mut svm, &payer, &mint, 100_000);
+            let contribute = contribute_ix(
+                &payer.pubkey(),
+                &mint,
+                &contributor.pubkey(),
+                &contributor_ata,
+                100_000,
+                vec![],
+            );
+            assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+
+            let contributor_reward =
+                CreateAssociatedTokenAccount::new(&mut svm, &contributor, &reward_mint)
+                    .owner(&contributor.pubkey())
+                    .send()
+                    .unwrap();
+            contributors.push((contributor, contributor_reward));
+        }
+
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+        let claim = claim_ix(&payer.pubkey(), &mint, &maker_ata);
+        assert!(send_ix(&mut svm, claim, &payer, &[&payer]));
+
+        // same amounts, but the first contributor came in with the full bonus
+        for ((contributor, contributor_reward), expected) in contributors.iter().zip([1_200, 1_000])
+        {
+            let claim = claim_reward_ix(
+                &fundraiser,
+                &reward_vault,
+                &contributor.pubkey(),
+                contributor_reward,
+            );
+            assert!(send_ix(&mut svm, claim, contributor, &[contributor]));
+            assert_eq!(token_balance(&svm, contributor_reward), expected);
+        }
+    }
+
+    fn matching_pool_accounts(fundraiser: &Pubkey, mint: &Pubkey) -> (Pubkey, Pubkey) {
+        let matching_pool = Pubkey::find_program_address(
+            &[b"matching".as_ref(), fundraiser.as_ref()],
+            &program_id(),
+        )
+        .0;
+        let pool_vault =
+            spl_associated_token_account::get_associated_token_address(&matching_pool, mint);
+        (matching_pool, pool_vault)
+    }
+
+    fn fund_matching_pool_ix(
+        fundraiser: &Pubkey,
+        mint: &Pubkey,
+        maker: &Pubkey,
+        sponsor: &Pubkey,
+        sponsor_ata: &Pubkey,
+        amount: u64,
+        donor_cap: u64,
+        ratio_bps: u16,
+    ) -> Instruction {
+        let (matching_pool, pool_vault) = matching_pool_accounts(fundraiser, mint);
+        let mut ix_data = crate::state::FundMatchingPoolIxData::default();
+        ix_data.set_amount(amount);
+        ix_data.set_donor_cap(donor_cap);
+        ix_data.set_ratio_bps(ratio_bps);
+        ix_data.set_padding();
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*sponsor, true),
+                AccountMeta::new_readonly(*maker, true),
+                AccountMeta::new_readonly(*fundraiser, false),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new(matching_pool, false),
+                AccountMeta::new(pool_vault, false),
+                AccountMeta::new(*sponsor_ata, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(
+                    ASSOCIATED_TOKEN_PROGRAM_ID.parse::<Pubkey>().unwrap(),
+                    false,
+                ),
+            ],
+            data: [vec![15u8], bytemuck::bytes_of(&ix_data).to_vec()].concat(),
+        }
+    }
+
+    fn withdraw_matching_pool_ix(
+        fundraiser: &Pubkey,
+        mint: &Pubkey,
+        sponsor: &Pubkey,
+        sponsor_ata: &Pubkey,
+    ) -> Instruction {
+        let (matching_pool, pool_vault) = matching_pool_accounts(fundraiser, mint);
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*sponsor, true),
+                AccountMeta::new_readonly(*fundraiser, false),
+                AccountMeta::new_readonly(matching_pool, false),
+                AccountMeta::new(pool_vault, false),
+                AccountMeta::new(*sponsor_ata, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+            data: vec![16u8],
+        }
+    }
+
+    #[test]
+    pub fn test_matching_pool() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, vault) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let (matching_pool, pool_vault) = matching_pool_accounts(&fundraiser, &mint);
+
+        let data = bytemuck::bytes_of(&initialize_data(1_000_000, 1, crate::state::FLAG_MATCHING))
+            .to_vec();
+        let init_ix = initialize_ix(&payer.pubkey(), &mint, data, vec![]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        // an empty account passed in place of the pool does not skip the match
+        let (first, first_ata) = new_contributor(&mut svm, &payer, &mint, 500_000);
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &first.pubkey(),
+            &first_ata,
+            80_000,
+            vec![
+                AccountMeta::new_readonly(Pubkey::new_unique(), false),
+                AccountMeta::new(Pubkey::new_unique(), false),
+            ],
+        );
+        assert!(!send_ix(&mut svm, contribute, &first, &[&first]));
+
+        // 1:1 up to 100_000 per donor
+        let (sponsor, sponsor_ata) = new_contributor(&mut svm, &payer, &mint, 300_000);
+        let fund = fund_matching_pool_ix(
+            &fundraiser,
+            &mint,
+            &payer.pubkey(),
+            &sponsor.pubkey(),
+            &sponsor_ata,
+            150_000,
+            100_000,
+            10_000,
+        );
+
+        // the pool and its terms can only be set up with the maker
+        let mut squat = fund.clone();
+        squat.accounts[1] = AccountMeta::new_readonly(sponsor.pubkey(), true);
+        assert!(!send_ix(&mut svm, squat, &sponsor, &[&sponsor]));
+
+        assert!(send_ix(&mut svm, fund, &sponsor, &[&sponsor, &payer]));
+        assert_eq!(token_balance(&svm, &pool_vault), 150_000);
+
+        let pool_accounts = vec![
+            AccountMeta::new_readonly(matching_pool, false),
+            AccountMeta::new(pool_vault, false),
+        ];
+
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &first.pubkey(),
+            &first_ata,
+            80_000,
+            pool_accounts.clone(),
+        );
+        assert!(send_ix(&mut svm, contribute, &first, &[&first]));
+        assert_eq!(token_balance(&svm, &vault), 160_000);
+
+        // capped per donor, then by what is left in the pool
+        let (second, second_ata) = new_contributor(&mut svm, &payer, &mint, 500_000);
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &second.pubkey(),
+            &second_ata,
+            200_000,
+            pool_accounts.clone(),
+        );
+        assert!(send_ix(&mut svm, contribute, &second, &[&second]));
+        assert_eq!(token_balance(&svm, &vault), 430_000);
+        assert_eq!(token_balance(&svm, &pool_vault), 0);
+
+        let withdraw =
+            withdraw_matching_pool_ix(&fundraiser, &mint, &sponsor.pubkey(), &sponsor_ata);
+        assert!(!send_ix(&mut svm, withdraw.clone(), &sponsor, &[&sponsor]));
+
+        advance_clock(&mut svm, 86_400);
+
+        // the refunded contribution's match goes back to the pool
+        let mut refund = refund_ix(&payer.pubkey(), &mint, &first.pubkey(), &first_ata);
+        refund.accounts.extend(pool_accounts);
+        assert!(send_ix(&mut svm, refund, &first, &[&first]));
+        assert_eq!(token_balance(&svm, &first_ata), 500_000);
+        assert_eq!(token_balance(&svm, &pool_vault), 80_000);
+
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, withdraw, &sponsor, &[&sponsor]));
+        assert_eq!(token_balance(&svm, &sponsor_ata), 230_000);
+    }
+
+    fn round_accounts(admin: &Pubkey, id: u64, mint: &Pubkey) -> (Pubkey, Pubkey) {
+        let round = Pubkey::find_program_address(
+            &[b"round".as_ref(), admin.as_ref(), &id.to_le_bytes()],
+            &program_id(),
+        )
+        .0;
+        let round_vault = spl_associated_token_account::get_associated_token_address(&round, mint);
+        (round, round_vault)
+    }
+
+    #[test]
+    pub fn test_quadratic_funding_round() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let ends_at = svm.get_sysvar::<Clock>().unix_timestamp as u64 + 3_600;
+        let create_round = |id: u64| {
+            let (round, round_vault) = round_accounts(&payer.pubkey(), id, &mint);
+            Instruction {
+                program_id: program_id(),
+                accounts: vec![
+                    AccountMeta::new(payer.pubkey(), true),
+                    AccountMeta::new(round, false),
+                    AccountMeta::new_readonly(mint, false),
+                    AccountMeta::new(round_vault, false),
+                    AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+                    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                    AccountMeta::new_readonly(
+                        ASSOCIATED_TOKEN_PROGRAM_ID.parse::<Pubkey>().unwrap(),
+                        false,
+                    ),
+                ],
+                data: [
+                    vec![17u8],
+                    ends_at.to_le_bytes().to_vec(),
+                    id.to_le_bytes().to_vec(),
+                ]
+                .concat(),
+            }
+        };
+        assert!(send_ix(&mut svm, create_round(0), &payer, &[&payer]));
+        let (round, round_vault) = round_accounts(&payer.pubkey(), 0, &mint);
+
+        // the same admin can open another round under a new id, but not reuse one
+        svm.expire_blockhash();
+        assert!(!send_ix(&mut svm, create_round(0), &payer, &[&payer]));
+        assert!(send_ix(&mut svm, create_round(1), &payer, &[&payer]));
+
+        MintTo::new(&mut svm, &payer, &mint, &round_vault, 1_000)
+            .owner(&payer)
+            .send()
+            .unwrap();
+
+        // two projects, each joining the round before any contribution
+        let second_maker = Keypair::new();
+        svm.airdrop(&second_maker.pubkey(), 2 * LAMPORTS_PER_SOL)
+            .expect("Airdrop failed");
+        let mut makers = vec![];
+        for (maker, target) in [(&payer, 40_000), (&second_maker, 1_000_000)] {
+            let data = bytemuck::bytes_of(&initialize_data(target, 1, 0)).to_vec();
+            let init_ix = initialize_ix(&maker.pubkey(), &mint, data, vec![]);
+            assert!(send_ix(&mut svm, init_ix, maker, &[maker]));
+
+            let (fundraiser, _, _) = fundraiser_accounts(&maker.pubkey(), &mint);
+            let join = Instruction {
+                program_id: program_id(),
+                accounts: vec![
+                    AccountMeta::new_readonly(maker.pubkey(), true),
+                    AccountMeta::new(fundraiser, false),
+                    AccountMeta::new(round, false),
+                ],
+                data: vec![18u8],
+            };
+            assert!(send_ix(&mut svm, join, maker, &[maker]));
+
+            let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, maker, &mint)
+                .owner(&maker.pubkey())
+                .send()
+                .unwrap();
+            makers.push((maker.pubkey(), maker_ata));
+        }
+
+        // a single contribution of 40_000 weighs as much as four of 2_500
+        for (maker, contributions) in [
+            (payer.pubkey(), vec![40_000]),
+            (second_maker.pubkey(), vec![2_500; 4]),
+        ] {
+            for amount in contributions {
+                let (contributor, contributor_ata) =
+                    new_contributor(&mut svm, &payer, &mint, amount);
+                let contribute = contribute_ix(
+                    &maker,
+                    &mint,
+                    &contributor.pubkey(),
+                    &contributor_ata,
+                    amount,
+                    vec![AccountMeta::new(round, false)],
+                );
+                assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+            }
+        }
+
+        let finalize = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(round, false),
+                AccountMeta::new_readonly(round_vault, false),
+            ],
+            data: vec![19u8],
+        };
+        assert!(!send_ix(&mut svm, finalize.clone(), &payer, &[&payer]));
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = ends_at as i64;
+        svm.set_sysvar::<Clock>(&clock);
+
+        // contributions made once the round ended no longer move the weights
+        let (contributor, contributor_ata) = new_contributor(&mut svm, &payer, &mint, 10_000);
+        let contribute = contribute_ix(
+            &second_maker.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+            10_000,
+            vec![AccountMeta::new(round, false)],
+        );
+        assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, finalize, &payer, &[&payer]));
+
+        let distribute_ix = |index: usize, destination: &Pubkey, maker: &Pubkey| {
+            let (fundraiser, _, _) = fundraiser_accounts(maker, &mint);
+            Instruction {
+                program_id: program_id(),
+                accounts: vec![
+                    AccountMeta::new(round, false),
+                    AccountMeta::new(round_vault, false),
+                    AccountMeta::new(*destination, false),
+                    AccountMeta::new_readonly(fundraiser, false),
+                    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                ],
+                data: vec![20u8, index as u8],
+            }
+        };
+
+        // the first project met its target, but its outcome is only final once it is paid out
+        // or the deadline passed
+        let (maker, maker_ata) = makers[0];
+        let distribute = distribute_ix(0, &maker_ata, &maker);
+        assert!(!send_ix(&mut svm, distribute.clone(), &payer, &[&payer]));
+
+        let claim = claim_ix(&payer.pubkey(), &mint, &maker_ata);
+        assert!(send_ix(&mut svm, claim, &payer, &[&payer]));
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, distribute.clone(), &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_ata), 40_500);
+
+        // each match is paid once
+        svm.expire_blockhash();
+        assert!(!send_ix(&mut svm, distribute, &payer, &[&payer]));
+
+        // the match of a cancelled project goes back to the admin
+        let cancel = cancel_ix(&second_maker.pubkey(), &mint);
+        assert!(send_ix(&mut svm, cancel, &second_maker, &[&second_maker]));
+
+        let (maker, maker_ata) = makers[1];
+        let distribute = distribute_ix(1, &maker_ata, &maker);
+        assert!(!send_ix(&mut svm, distribute, &payer, &[&payer]));
+
+        let admin_ata = makers[0].1;
+        let distribute = distribute_ix(1, &admin_ata, &maker);
+        assert!(send_ix(&mut svm, distribute, &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_ata), 0);
+        assert_eq!(token_balance(&svm, &admin_ata), 41_000);
+    }
+
+    #[test]
+    pub fn test_umbrella_campaign() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let campaign = Pubkey::find_program_address(
+            &[b"campaign".as_ref(), payer.pubkey().as_ref()],
+            &program_id(),
+        )
+        .0;
+
+        let create_campaign = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(campaign, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+            ],
+            data: vec![21u8],
+        };
+        assert!(send_ix(&mut svm, create_campaign, &payer, &[&payer]));
+
+        // the campaign owner co-signs the initialization of each child
+        let second_maker = Keypair::new();
+        svm.airdrop(&second_maker.pubkey(), 2 * LAMPORTS_PER_SOL)
+            .expect("Airdrop failed");
+        for (maker, target) in [(&payer, 300_000), (&second_maker, 700_000)] {
+            let data = bytemuck::bytes_of(&initialize_data(target, 1, crate::state::FLAG_CAMPAIGN))
+                .to_vec();
+            let init_ix = initialize_ix(
+                &maker.pubkey(),
+                &mint,
+                data,
+                vec![
+                    AccountMeta::new(campaign, false),
+                    AccountMeta::new_readonly(payer.pubkey(), true),
+                ],
+            );
+            assert!(send_ix(&mut svm, init_ix, maker, &[maker, &payer]));
+        }
+
+        // only the owner's campaign PDA can be joined, not another account of the program
+        let third_maker = Keypair::new();
+        svm.airdrop(&third_maker.pubkey(), 2 * LAMPORTS_PER_SOL)
+            .expect("Airdrop failed");
+        let (payer_fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let data =
+            bytemuck::bytes_of(&initialize_data(100_000, 1, crate::state::FLAG_CAMPAIGN)).to_vec();
+        let init_ix = initialize_ix(
+            &third_maker.pubkey(),
+            &mint,
+            data,
+            vec![
+                AccountMeta::new(payer_fundraiser, false),
+                AccountMeta::new_readonly(payer.pubkey(), true),
+            ],
+        );
+        assert!(!send_ix(
+            &mut svm,
+            init_ix,
+            &third_maker,
+            &[&third_maker, &payer]
+        ));
+
+        let (first, first_ata) = new_contributor(&mut svm, &payer, &mint, 500_000);
+        let (second, second_ata) = new_contributor(&mut svm, &payer, &mint, 500_000);
+        let contributions = [
+            (payer.pubkey(), &first, first_ata, 100_000),
+            (payer.pubkey(), &first, first_ata, 50_000),
+            (second_maker.pubkey(), &first, first_ata, 20_000),
+            (second_maker.pubkey(), &second, second_ata, 30_000),
+        ];
+        for (maker, contributor, contributor_ata, amount) in contributions {
+            let contribute = contribute_ix(
+                &maker,
+                &mint,
+                &contributor.pubkey(),
+                &contributor_ata,
+                amount,
+                vec![AccountMeta::new(campaign, false)],
+            );
+            assert!(send_ix(&mut svm, contribute, contributor, &[contributor]));
+            svm.expire_blockhash();
+        }
+
+        let campaign_state = |svm: &LiteSVM| {
+            let data = svm.get_account(&campaign).unwrap().data;
+            *bytemuck::from_bytes::<crate::state::CampaignData>(&data)
+        };
+        let state = campaign_state(&svm);
+        assert_eq!(state.child_count(), 2);
+        assert_eq!(state.total_target(), 1_000_000);
+        assert_eq!(state.total_raised(), 200_000);
+        // the first contributor backs both children
+        assert_eq!(state.contributor_count(), 3);
+
+        let cancel = cancel_ix(&second_maker.pubkey(), &mint);
+        assert!(send_ix(&mut svm, cancel, &second_maker, &[&second_maker]));
+        let mut refund = refund_ix(&second_maker.pubkey(), &mint, &second.pubkey(), &second_ata);
+        refund.accounts.push(AccountMeta::new(campaign, false));
+        assert!(send_ix(&mut svm, refund, &second, &[&second]));
+
+        let state = campaign_state(&svm);
+        assert_eq!(state.total_raised(), 170_000);
+        assert_eq!(state.contributor_count(), 2);
+    }
+
+    #[test]
+    pub fn test_donor_directed_allocation() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let allocations = Pubkey::find_program_address(
+            &[b"allocations".as_ref(), fundraiser.as_ref()],
+            &program_id(),
+        )
+        .0;
+
+        let beneficiaries = [Keypair::new(), Keypair::new()];
+        let mut beneficiaries_ix = crate::state::BeneficiariesIxData::default();
+        beneficiaries_ix.set_count(2);
+        beneficiaries_ix.set_padding();
+        for (index, beneficiary) in beneficiaries.iter().enumerate() {
+            beneficiaries_ix.set_wallet(index, &beneficiary.pubkey().to_bytes());
+        }
+
+        let data = [
+            bytemuck::bytes_of(&initialize_data(100_000, 1, crate::state::FLAG_ALLOCATION))
+                .to_vec(),
+            bytemuck::bytes_of(&beneficiaries_ix).to_vec(),
+        ]
+        .concat();
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            data,
+            vec![AccountMeta::new(allocations, false)],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let allocation = |bps: [u16; 2]| {
+            let mut allocation = crate::state::AllocationIxData::default();
+            allocation.set_bps(0, bps[0]);
+            allocation.set_bps(1, bps[1]);
+            bytemuck::bytes_of(&allocation).to_vec()
+        };
+
+        let (first, first_ata) = new_contributor(&mut svm, &payer, &mint, 100_000);
+        let (second, second_ata) = new_contributor(&mut svm, &payer, &mint, 100_000);
+        let (third, third_ata) = new_contributor(&mut svm, &payer, &mint, 100_000);
+        let contributions = [
+            (&first, first_ata, 60_000, [10_000, 0]),
+            (&second, second_ata, 30_000, [2_500, 7_500]),
+            (&third, third_ata, 20_000, [5_000, 5_000]),
+        ];
+        for (contributor, contributor_ata, amount, bps) in contributions {
+            let mut contribute = contribute_ix(
+                &payer.pubkey(),
+                &mint,
+                &contributor.pubkey(),
+                &contributor_ata,
+                amount,
+                vec![AccountMeta::new(allocations, false)],
+            );
+            contribute.data.extend(allocation(bps));
+            assert!(send_ix(&mut svm, contribute, contributor, &[contributor]));
+        }
+
+        // an allocation has to cover the whole contribution
+        svm.expire_blockhash();
+        let mut contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &first.pubkey(),
+            &first_ata,
+            10_000,
+            vec![AccountMeta::new(allocations, false)],
+        );
+        contribute.data.extend(allocation([5_000, 4_000]));
+        assert!(!send_ix(&mut svm, contribute, &first, &[&first]));
+
+        let allocations_state = |svm: &LiteSVM| {
+            let data = svm.get_account(&allocations).unwrap().data;
+            *bytemuck::from_bytes::<crate::state::AllocationsData>(&data)
+        };
+        let state = allocations_state(&svm);
+        assert_eq!(state.beneficiaries[0].total(), 77_500);
+        assert_eq!(state.beneficiaries[1].total(), 32_500);
+
+        // a refund takes the contribution back from the beneficiaries
+        assert!(send_ix(
+            &mut svm,
+            cancel_ix(&payer.pubkey(), &mint),
+            &payer,
+            &[&payer]
+        ));
+        let mut refund = refund_ix(&payer.pubkey(), &mint, &third.pubkey(), &third_ata);
+        refund.accounts.push(AccountMeta::new(allocations, false));
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, refund, &third, &[&third]));
+
+        let state = allocations_state(&svm);
+        assert_eq!(state.beneficiaries[0].total(), 67_500);
+        assert_eq!(state.beneficiaries[1].total(), 22_500);
+    }
+
+    #[test]
+    pub fn test_allocation_claim() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, _) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let allocations = Pubkey::find_program_address(
+            &[b"allocations".as_ref(), fundraiser.as_ref()],
+            &program_id(),
+        )
+        .0;
+
+        let beneficiary = Keypair::new();
+        svm.airdrop(&beneficiary.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Airdrop failed");
+        let beneficiary_ata = CreateAssociatedTokenAccount::new(&mut svm, &beneficiary, &mint)
+            .owner(&beneficiary.pubkey())
+            .token_program_id(&TOKEN_PROGRAM_ID)
+            .send()
+            .unwrap();
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint)
+            .owner(&payer.pubkey())
+            .token_program_id(&TOKEN_PROGRAM_ID)
+            .send()
+            .unwrap();
+
+        let mut beneficiaries_ix = crate::state::BeneficiariesIxData::default();
+        beneficiaries_ix.set_count(1);
+        beneficiaries_ix.set_padding();
+        beneficiaries_ix.set_wallet(0, &beneficiary.pubkey().to_bytes());
+
+        let data = [
+            bytemuck::bytes_of(&initialize_data(50_000, 1, crate::state::FLAG_ALLOCATION)).to_vec(),
+            bytemuck::bytes_of(&beneficiaries_ix).to_vec(),
+        ]
+        .concat();
+        let init_ix = initialize_ix(
+            &payer.pubkey(),
+            &mint,
+            data,
+            vec![AccountMeta::new(allocations, false)],
+        );
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (contributor, contributor_ata) = new_contributor(&mut svm, &payer, &mint, 100_000);
+        let mut allocation = crate::state::AllocationIxData::default();
+        allocation.set_bps(0, 10_000);
+        let mut contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &contributor.pubkey(),
+            &contributor_ata,
+            50_000,
+            vec![AccountMeta::new(allocations, false)],
+        );
+        contribute.data.extend(bytemuck::bytes_of(&allocation));
+        assert!(send_ix(&mut svm, contribute, &contributor, &[&contributor]));
+
+        // the whole raise was directed to the beneficiary
+        let mut claim = claim_ix(&payer.pubkey(), &mint, &maker_ata);
+        claim.accounts.extend([
+            AccountMeta::new(allocations, false),
+            AccountMeta::new(beneficiary_ata, false),
+        ]);
+        assert!(send_ix(&mut svm, claim, &payer, &[&payer]));
+
+        assert_eq!(token_balance(&svm, &beneficiary_ata), 50_000);
+        assert_eq!(token_balance(&svm, &maker_ata), 0);
+        assert!(
+            svm.get_account(&allocations)
+                .map_or(true, |account| account.data.is_empty())
+        );
+    }
+
+    fn approve_ix(source: &Pubkey, delegate: &Pubkey, owner: &Pubkey, amount: u64) -> Instruction {
+        Instruction {
+            program_id: TOKEN_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(*source, false),
+                AccountMeta::new_readonly(*delegate, false),
+                AccountMeta::new_readonly(*owner, true),
+            ],
+            data: [vec![4u8], amount.to_le_bytes().to_vec()].concat(),
+        }
+    }
+
+    #[test]
+    pub fn test_pledge_collection() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, vault) = fundraiser_accounts(&payer.pubkey(), &mint);
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint)
+            .owner(&payer.pubkey())
+            .token_program_id(&TOKEN_PROGRAM_ID)
+            .send()
+            .unwrap();
+
+        let data =
+            bytemuck::bytes_of(&initialize_data(100_000, 1, crate::state::FLAG_PLEDGE)).to_vec();
+        let init_ix = initialize_ix(&payer.pubkey(), &mint, data, vec![]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (first, first_ata) = new_contributor(&mut svm, &payer, &mint, 100_000);
+        let (second, second_ata) = new_contributor(&mut svm, &payer, &mint, 100_000);
+
+        // a pledge needs the fundraiser approved as delegate for the whole amount
+        let contribute = contribute_ix(
+            &payer.pubkey(),
+            &mint,
+            &first.pubkey(),
+            &first_ata,
+            70_000,
+            vec![],
+        );
+        assert!(!send_ix(&mut svm, contribute.clone(), &first, &[&first]));
+
+        for (contributor, contributor_ata, amount) in
+            [(&first, first_ata, 70_000), (&second, second_ata, 50_000)]
+        {
+            let approve = approve_ix(&contributor_ata, &fundraiser, &contributor.pubkey(), amount);
+            assert!(send_ix(&mut svm, approve, contributor, &[contributor]));
+            let contribute = contribute_ix(
+                &payer.pubkey(),
+                &mint,
+                &contributor.pubkey(),
+                &contributor_ata,
+                amount,
+                vec![],
+            );
+            svm.expire_blockhash();
+            assert!(send_ix(&mut svm, contribute, contributor, &[contributor]));
+        }
+
+        // nothing is paid in, and there is nothing to refund
+        assert_eq!(token_balance(&svm, &vault), 0);
+        assert_eq!(token_balance(&svm, &first_ata), 100_000);
+        let refund = refund_ix(&payer.pubkey(), &mint, &first.pubkey(), &first_ata);
+        assert!(!send_ix(&mut svm, refund, &first, &[&first]));
+
+        // the second pledger only keeps part of the pledge approved
+        let approve = approve_ix(&second_ata, &fundraiser, &second.pubkey(), 20_000);
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, approve, &second, &[&second]));
+
+        let collect_ix = |pledgers: &[(&Keypair, Pubkey)]| {
+            let mut accounts = vec![
+                AccountMeta::new(fundraiser, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ];
+            for (contributor, contributor_ata) in pledgers {
+                accounts.extend([
+                    AccountMeta::new_readonly(contributor.pubkey(), false),
+                    AccountMeta::new(contributor_pda(&fundraiser, &contributor.pubkey()).0, false),
+                    AccountMeta::new(*contributor_ata, false),
+                ]);
+            }
+            Instruction {
+                program_id: program_id(),
+                accounts,
+                data: vec![22u8],
+            }
+        };
+
+        // pledges are collected once the fundraiser has ended
+        let pledgers = [(&first, first_ata), (&second, second_ata)];
+        assert!(!send_ix(&mut svm, collect_ix(&pledgers), &payer, &[&payer]));
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 2 * 86_400;
+        svm.set_sysvar::<Clock>(&clock);
+
+        let claim = claim_ix(&payer.pubkey(), &mint, &maker_ata);
+        svm.expire_blockhash();
+        assert!(!send_ix(&mut svm, claim, &payer, &[&payer]));
+
+        assert!(send_ix(&mut svm, collect_ix(&pledgers), &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &vault), 90_000);
+        assert_eq!(token_balance(&svm, &first_ata), 30_000);
+        assert_eq!(token_balance(&svm, &second_ata), 80_000);
+
+        let data = svm.get_account(&fundraiser).unwrap().data;
+        let state = *bytemuck::from_bytes::<crate::state::FundraiserData>(&data);
+        assert_eq!(state.collected_amount(), 90_000);
+        assert_eq!(state.shortfall_amount(), 30_000);
+
+        let data = svm
+            .get_account(&contributor_pda(&fundraiser, &second.pubkey()).0)
+            .unwrap()
+            .data;
+        let state = *bytemuck::from_bytes::<crate::state::ContributorData>(&data);
+        assert!(state.collected());
+        assert_eq!(state.shortfall(), 30_000);
+
+        // collected pledges are skipped, and the maker claims what was collected
+        svm.expire_blockhash();
+        assert!(send_ix(&mut svm, collect_ix(&pledgers), &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &vault), 90_000);
+
+        let claim = claim_ix(&payer.pubkey(), &mint, &maker_ata);
+        assert!(send_ix(&mut svm, claim, &payer, &[&payer]));
+        assert_eq!(token_balance(&svm, &maker_ata), 90_000);
+    }
+
+    #[test]
+    pub fn test_recurring_subscription() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, vault) = fundraiser_accounts(&payer.pubkey(), &mint);
+
+        let data = bytemuck::bytes_of(&initialize_data(100_000, 7, 0)).to_vec();
+        let init_ix = initialize_ix(&payer.pubkey(), &mint, data, vec![]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (contributor, contributor_ata) = new_contributor(&mut svm, &payer, &mint, 100_000);
+        let (contributor_account, _) = contributor_pda(&fundraiser, &contributor.pubkey());
+        let subscription = Pubkey::find_program_address(
+            &[
+                b"subscription".as_ref(),
+                fundraiser.as_ref(),
+                contributor.pubkey().as_ref(),
+            ],
+            &program_id(),
+        )
+        .0;
+
+        let approve = approve_ix(
+            &contributor_ata,
+            &subscription,
+            &contributor.pubkey(),
+            30_000,
+        );
+        assert!(send_ix(&mut svm, approve, &contributor, &[&contributor]));
+
+        let mut subscription_ix = crate::state::SubscriptionIxData::default();
+        subscription_ix.set_amount(10_000);
+        subscription_ix.set_interval(86_400);
+        subscription_ix.set_bounty(LAMPORTS_PER_SOL / 100);
+        subscription_ix.set_periods(3);
+        subscription_ix.set_padding();
+        let create = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(fundraiser, false),
+                AccountMeta::new(contributor_account, false),
+                AccountMeta::new(subscription, false),
+                AccountMeta::new_readonly(contributor_ata, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+            ],
+            data: [vec![23u8], bytemuck::bytes_of(&subscription_ix).to_vec()].concat(),
+        };
+        assert!(send_ix(&mut svm, create, &contributor, &[&contributor]));
+
+        let cranker = Keypair::new();
+        svm.airdrop(&cranker.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Airdrop failed");
+        let process = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(cranker.pubkey(), true),
+                AccountMeta::new(contributor.pubkey(), false),
+                AccountMeta::new(fundraiser, false),
+                AccountMeta::new(contributor_account, false),
+                AccountMeta::new(subscription, false),
+                AccountMeta::new(contributor_ata, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+            data: vec![24u8],
+        };
+
+        // the first payment is due right away and pays the cranker its bounty
+        let before = svm.get_balance(&cranker.pubkey()).unwrap();
+        assert!(send_ix(&mut svm, process.clone(), &cranker, &[&cranker]));
+        assert!(svm.get_balance(&cranker.pubkey()).unwrap() > before);
+        assert_eq!(token_balance(&svm, &vault), 10_000);
+
+        // the next one only after the interval
+        svm.expire_blockhash();
+        assert!(!send_ix(&mut svm, process.clone(), &cranker, &[&cranker]));
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 86_400;
+        svm.set_sysvar::<Clock>(&clock);
+        assert!(send_ix(&mut svm, process, &cranker, &[&cranker]));
+        assert_eq!(token_balance(&svm, &vault), 20_000);
+        assert_eq!(token_balance(&svm, &contributor_ata), 80_000);
+
+        let data = svm.get_account(&contributor_account).unwrap().data;
+        let state = *bytemuck::from_bytes::<crate::state::ContributorData>(&data);
+        assert_eq!(state.amount(), 20_000);
+
+        // the contributor stops before the last period and gets the last bounty back
+        let cancel = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(fundraiser, false),
+                AccountMeta::new(subscription, false),
+            ],
+            data: vec![25u8],
+        };
+        assert!(send_ix(&mut svm, cancel, &contributor, &[&contributor]));
+        assert!(
+            svm.get_account(&subscription)
+                .map_or(true, |account| account.data.is_empty())
+        );
+    }
+
+    #[test]
+    pub fn test_relayed_contribution() {
+        let (mut svm, payer) = setup();
+
+        let mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser, _, vault) = fundraiser_accounts(&payer.pubkey(), &mint);
+
+        let data = bytemuck::bytes_of(&initialize_data(100_000, 7, 0)).to_vec();
+        let init_ix = initialize_ix(&payer.pubkey(), &mint, data, vec![]);
+        assert!(send_ix(&mut svm, init_ix, &payer, &[&payer]));
+
+        let (contributor, contributor_ata) = new_contributor(&mut svm, &payer, &mint, 100_000);
+        let approve = approve_ix(&contributor_ata, &fundraiser, &contributor.pubkey(), 50_000);
+        assert!(send_ix(&mut svm, approve, &contributor, &[&contributor]));
+
+        // the relayer pays for everything, the contributor only signs the intent
+        let relayer = Keypair::new();
+        svm.airdrop(&relayer.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Airdrop failed");
+
+        let relay = |svm: &LiteSVM, amount: u64, nonce: u64, signer: &Keypair| {
+            let expiry = svm.get_sysvar::<Clock>().unix_timestamp as u64 + 3_600;
+            let mut intent = crate::state::ContributionIntent::default();
+            intent.set_fundraiser(&fundraiser.to_bytes());
+            intent.set_amount(amount);
+            intent.set_nonce(nonce);
+            intent.set_expiry(expiry);
+            let message = bytemuck::bytes_of(&intent).to_vec();
+
+            let signature = signer.sign_message(&message);
+            let mut ed25519_data = vec![1u8, 0];
+            for value in [
+                48u16,
+                u16::MAX,
+                16,
+                u16::MAX,
+                112,
+                message.len() as u16,
+                u16::MAX,
+            ] {
+                ed25519_data.extend_from_slice(&value.to_le_bytes());
+            }
+            ed25519_data.extend_from_slice(signer.pubkey().as_ref());
+            ed25519_data.extend_from_slice(signature.as_ref());
+            ed25519_data.extend_from_slice(&message);
+            let verify = Instruction {
+                program_id: "Ed25519SigVerify111111111111111111111111111"
+                    .parse::<Pubkey>()
+                    .unwrap(),
+                accounts: vec![],
+                data: ed25519_data,
+            };
+
+            let nonce_pda = Pubkey::find_program_address(
+                &[
+                    b"nonce".as_ref(),
+                    contributor.pubkey().as_ref(),
+                    &nonce.to_le_bytes(),
+                ],
+                &program_id(),
+            )
+            .0;
+            let contribute = Instruction {
+                program_id: program_id(),
+                accounts: vec![
+                    AccountMeta::new(relayer.pubkey(), true),
+                    AccountMeta::new_readonly(contributor.pubkey(), false),
+                    AccountMeta::new(fundraiser, false),
+                    AccountMeta::new(contributor_pda(&fundraiser, &contributor.pubkey()).0, false),
+                    AccountMeta::new(contributor_ata, false),
+                    AccountMeta::new(vault, false),
+                    AccountMeta::new(nonce_pda, false),
+                    AccountMeta::new_readonly(solana_sdk_ids::sysvar::instructions::ID, false),
+                    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                    AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+                ],
+                data: [vec![26u8], message].concat(),
+            };
+
+            let message = Message::new(&[verify, contribute], Some(&relayer.pubkey()));
+            Transaction::new(&[&relayer], message, svm.latest_blockhash())
+        };
+
+        let contributor_before = svm.get_balance(&contributor.pubkey()).unwrap();
+        let transaction = relay(&svm, 20_000, 1, &contributor);
+        assert!(svm.send_transaction(transaction).is_ok());
+        assert_eq!(token_balance(&svm, &vault), 20_000);
+        assert_eq!(
+            svm.get_balance(&contributor.pubkey()).unwrap(),
+            contributor_before
+        );
+
+        // an intent is only accepted once
+        svm.expire_blockhash();
+        let transaction = relay(&svm, 20_000, 1, &contributor);
+        assert!(svm.send_transaction(transaction).is_err());
+
+        // and only when signed by the contributor
+        let transaction = relay(&svm, 20_000, 2, &relayer);
+        assert!(svm.send_transaction(transaction).is_err());
+
+        let transaction = relay(&svm, 10_000, 2, &contributor);
+        assert!(svm.send_transaction(transaction).is_ok());
+        assert_eq!(token_balance(&svm, &vault), 30_000);
+
+        let data = svm
+            .get_account(&contributor_pda(&fundraiser, &contributor.pubkey()).0)
+            .unwrap()
+            .data;
+        let state = *bytemuck::from_bytes::<crate::state::ContributorData>(&data);
+        assert_eq!(state.amount(), 30_000);
+    }
 }
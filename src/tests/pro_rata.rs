@@ -0,0 +1,83 @@
+use crate::helpers::pro_rata_share;
+
+/// Small xorshift generator so the property tests stay reproducible without extra
+/// dependencies.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+/// Refunds every contributor, in random order, from a vault that had a random part
+/// of the raise released already, checking the invariants after each refund.
+fn refund_everyone(seed: u64) {
+    let mut rng = XorShift(seed);
+
+    let contributors = 2 + rng.below(200) as usize;
+    let mut amounts: Vec<u64> = (0..contributors)
+        .map(|_| 1 + rng.below(1_000_000_000))
+        .collect();
+
+    let mut total: u64 = amounts.iter().sum();
+    let mut vault = total - rng.below(total + 1);
+
+    // shuffle the refund order
+    for i in (1..amounts.len()).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        amounts.swap(i, j);
+    }
+
+    for amount in amounts {
+        let share = pro_rata_share(amount, vault, total).unwrap();
+
+        assert!(share <= vault);
+        // never more than the exact share
+        assert!(share as u128 * total as u128 <= amount as u128 * vault as u128);
+
+        let (vault_before, total_before) = (vault, total);
+        vault -= share;
+        total -= amount;
+
+        // what is left per outstanding unit never shrinks, so later refunds are covered
+        assert!(vault as u128 * total_before as u128 >= vault_before as u128 * total as u128);
+    }
+
+    assert_eq!(total, 0);
+    assert_eq!(vault, 0, "the last refund sweeps the rounding dust");
+}
+
+#[test]
+fn pro_rata_refunds_never_drain_other_entitlements() {
+    for seed in 1..=500 {
+        refund_everyone(seed);
+    }
+}
+
+#[test]
+fn untouched_vault_refunds_exact_amounts() {
+    let amounts = [7u64, 1, 999_999, 42, 123_456_789];
+    let mut total: u64 = amounts.iter().sum();
+    let mut vault = total;
+
+    for amount in amounts {
+        let share = pro_rata_share(amount, vault, total).unwrap();
+        assert_eq!(share, amount);
+        vault -= share;
+        total -= amount;
+    }
+}
+
+#[test]
+fn share_above_total_is_rejected() {
+    assert!(pro_rata_share(11, 100, 10).is_err());
+    assert!(pro_rata_share(1, 100, 0).is_err());
+}
@@ -0,0 +1,41 @@
+use crate::helpers::{isqrt, quadratic_match};
+
+#[test]
+fn isqrt_rounds_down() {
+    for value in 0..10_000u64 {
+        let root = isqrt(value);
+        assert!(root * root <= value);
+        assert!((root + 1) * (root + 1) > value);
+    }
+
+    assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+    assert_eq!(isqrt(1 << 62), 1 << 31);
+    assert_eq!(isqrt((1 << 62) - 1), (1 << 31) - 1);
+}
+
+#[test]
+fn quadratic_match_favours_many_small_contributions() {
+    // one contributor of 10_000 against a hundred contributors of 1
+    let whale = (isqrt(10_000) as u128).pow(2);
+    let crowd = (100 * isqrt(1) as u128).pow(2);
+    let total = whale + crowd;
+
+    assert_eq!(quadratic_match(1_000, whale, total).unwrap(), 500);
+    assert_eq!(quadratic_match(1_000, crowd, total).unwrap(), 500);
+}
+
+#[test]
+fn quadratic_match_never_exceeds_the_pool() {
+    let weights = [u128::MAX / 4, u128::MAX / 8, 3, u128::MAX / 3];
+    let total: u128 = weights.iter().sum();
+
+    let paid: u128 = weights
+        .iter()
+        .map(|weight| quadratic_match(u64::MAX, *weight, total).unwrap() as u128)
+        .sum();
+    assert!(paid <= u64::MAX as u128);
+    assert_eq!(quadratic_match(u64::MAX, total, total).unwrap(), u64::MAX);
+
+    assert!(quadratic_match(1, 2, 1).is_err());
+    assert!(quadratic_match(1, 0, 0).is_err());
+}